@@ -1,41 +1,317 @@
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
 
 use crate::config::Config;
-use crate::spec_store::SpecStore;
+use crate::flag_values::flag_key;
+use crate::spec::{CommandSpec, SubcommandSpec};
+use crate::spec_bundle;
+use crate::spec_store::{DiscoveryMethod, SpecStore};
 
+use super::completions::installed_bundle;
 use super::scan::resolve_completions_dir;
+use super::translate::prompt;
+
+/// Names listed per section of the preview before eliding the rest.
+const PREVIEW_NAMES: usize = 12;
 
+/// Discover a spec for `command` (a command name, or a path such as
+/// `aws s3`), show what was found, and write its completion file. At a
+/// terminal the user confirms first and may edit the spec in `$EDITOR`.
+/// Edited specs and subcommand paths are stored as bundles in the specs
+/// directory, so `synapse completions sync` regenerates from them rather
+/// than rediscovering.
 pub(super) async fn add_command(
-    command: String,
+    command: Vec<String>,
+    edit: bool,
     output_dir: Option<PathBuf>,
 ) -> anyhow::Result<()> {
     let config = Config::load();
     let completions_dir = resolve_completions_dir(&config, output_dir);
 
-    let spec_store = SpecStore::with_completions_dir(config.spec.clone(), completions_dir);
+    let spec_store = SpecStore::with_completions_dir(config.spec.clone(), completions_dir.clone());
 
-    if !spec_store.can_discover_command(&command) {
-        eprintln!("Cannot discover '{command}': blocked by safety blocklist or config");
+    let (name, path) = command.split_first().context("no command given")?;
+    let label = command.join(" ");
+
+    if !spec_store.can_discover_command(name) {
+        eprintln!("Cannot discover '{name}': blocked by safety blocklist or config");
         std::process::exit(1);
     }
 
-    if spec_store.has_system_completion(&command) {
-        eprintln!("'{command}' already has completions installed (found in zsh fpath)");
+    if spec_store.has_system_completion(name) {
+        eprintln!("'{name}' already has completions installed (found in zsh fpath)");
         std::process::exit(1);
     }
 
-    match spec_store.discover_command(&command).await {
-        Some((spec, path)) => {
-            let n_opts = spec.options.len();
-            let n_subs = spec.subcommands.len();
-            println!("Discovered {command}: {n_opts} options, {n_subs} subcommands");
-            println!("  Wrote {}", path.display());
+    let specs_dir = spec_bundle::specs_dir().context("cannot determine home directory")?;
+    let discovered = if path.is_empty() {
+        spec_store.discover_spec(name).await
+    } else {
+        // Build on the stored spec so `add aws s3` then `add aws ec2` keeps both.
+        let base = match installed_bundle(&specs_dir, name) {
+            Some((spec, _)) => spec,
+            None => spec_store
+                .discover_spec(name)
+                .await
+                .unwrap_or_else(|| CommandSpec {
+                    name: name.clone(),
+                    ..Default::default()
+                }),
+        };
+        spec_store.discover_subcommand(base, path).await
+    };
+    let Some(mut spec) = discovered else {
+        eprintln!("No spec discovered for '{label}' (--help produced no parseable output)");
+        std::process::exit(1);
+    };
+
+    print_preview(&spec, path, &label);
+    let mut edited = false;
+    if edit {
+        spec = edit_spec(spec)?;
+        edited = true;
+        print_preview(&spec, path, &label);
+    }
+    if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        loop {
+            match prompt("Write completions? [Y/n/e(dit)] ")?
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "y" | "yes" => break,
+                "e" | "edit" => {
+                    spec = edit_spec(spec)?;
+                    edited = true;
+                    print_preview(&spec, path, &label);
+                }
+                _ => {
+                    println!("Nothing written");
+                    return Ok(());
+                }
+            }
         }
-        None => {
-            eprintln!("No spec discovered for '{command}' (--help produced no parseable output)");
-            std::process::exit(1);
+    }
+
+    if edited || !path.is_empty() {
+        let bundle = spec_bundle::export(&spec);
+        spec = spec_bundle::install(bundle, "synapse add", true, &specs_dir)?;
+        println!(
+            "  Saved spec to {}",
+            specs_dir.join(format!("{name}.toml")).display()
+        );
+    }
+    let written = crate::compsys_export::write_completion_file(&spec, &completions_dir)?;
+    println!("  Wrote {}", written.display());
+
+    Ok(())
+}
+
+/// Add every command named by `patterns` (command names or globs such as
+/// `kube*`, matched against executables on PATH) and report what was added,
+/// skipped and failed. Commands that already have completions are skipped,
+/// so re-running after installing new tools only adds the new ones.
+pub(super) async fn add_from_path(
+    patterns: Vec<String>,
+    output_dir: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let config = Config::load();
+    let completions_dir = resolve_completions_dir(&config, output_dir);
+    let spec_store = SpecStore::with_completions_dir(config.spec.clone(), completions_dir.clone());
+
+    let (commands, mut failed) = matching_commands(&patterns);
+    let mut added: Vec<(String, DiscoveryMethod)> = Vec::new();
+    let mut skipped: Vec<(String, &str)> = Vec::new();
+    for command in commands {
+        if !spec_store.can_discover_command(&command) {
+            skipped.push((command, "blocked"));
+        } else if spec_store.has_system_completion(&command) {
+            skipped.push((command, "system completions"));
+        } else if completions_dir.join(format!("_{command}")).exists() {
+            skipped.push((command, "already added"));
+        } else {
+            match spec_store.discover_spec_via(&command).await {
+                Some((spec, method)) => {
+                    crate::compsys_export::write_completion_file(&spec, &completions_dir)?;
+                    added.push((command, method));
+                }
+                None => failed.push((command, "no parseable --help output")),
+            }
         }
     }
 
+    println!(
+        "Added {} completions in {}",
+        added.len(),
+        completions_dir.display()
+    );
+    for method in [
+        DiscoveryMethod::Builtin,
+        DiscoveryMethod::Generator,
+        DiscoveryMethod::Help,
+    ] {
+        let names: Vec<&str> = added
+            .iter()
+            .filter(|(_, m)| *m == method)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        print_group(&format!("via {}", method.name()), &names);
+    }
+    if !skipped.is_empty() {
+        println!("Skipped {}", skipped.len());
+        print_reasons(&skipped);
+    }
+    if !failed.is_empty() {
+        println!("Failed {}", failed.len());
+        print_reasons(&failed);
+    }
     Ok(())
 }
+
+/// Executables on PATH matching any of `patterns`, sorted, plus the literal
+/// names that are not on PATH.
+fn matching_commands(patterns: &[String]) -> (BTreeSet<String>, Vec<(String, &'static str)>) {
+    let on_path: BTreeSet<String> = std::env::var_os("PATH")
+        .map(|path| {
+            std::env::split_paths(&path)
+                .flat_map(|dir| crate::spec_autogen::executables_in(&dir))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut commands = BTreeSet::new();
+    let mut missing = Vec::new();
+    for pattern in patterns {
+        if !pattern.contains(['*', '?']) {
+            if on_path.contains(pattern) {
+                commands.insert(pattern.clone());
+            } else {
+                missing.push((pattern.clone(), "not found on PATH"));
+            }
+            continue;
+        }
+        let body = regex::escape(pattern)
+            .replace(r"\*", ".*")
+            .replace(r"\?", ".");
+        let re = regex::Regex::new(&format!("^{body}$")).expect("escaped glob is a valid regex");
+        commands.extend(on_path.iter().filter(|name| re.is_match(name)).cloned());
+    }
+    (commands, missing)
+}
+
+fn print_reasons(entries: &[(String, &str)]) {
+    let reasons: BTreeSet<&str> = entries.iter().map(|(_, reason)| *reason).collect();
+    for reason in reasons {
+        let names: Vec<&str> = entries
+            .iter()
+            .filter(|(_, r)| *r == reason)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        print_group(reason, &names);
+    }
+}
+
+fn print_group(label: &str, names: &[&str]) {
+    if !names.is_empty() {
+        println!("  {label} ({}): {}", names.len(), names.join(", "));
+    }
+}
+
+/// Summarize the options and subcommands found at `path` in `spec`.
+fn print_preview(spec: &CommandSpec, path: &[String], label: &str) {
+    let (options, subcommands) = match find_subcommand(&spec.subcommands, path) {
+        Some(sub) => (&sub.options, &sub.subcommands),
+        None => (&spec.options, &spec.subcommands),
+    };
+    println!(
+        "Discovered {label}: {} options, {} subcommands",
+        options.len(),
+        subcommands.len()
+    );
+    print_names(
+        "Subcommands",
+        subcommands.iter().map(|s| s.name.as_str()).collect(),
+    );
+    print_names(
+        "Options",
+        options.iter().filter_map(|o| flag_key(o)).collect(),
+    );
+}
+
+fn find_subcommand<'a>(
+    subcommands: &'a [SubcommandSpec],
+    path: &[String],
+) -> Option<&'a SubcommandSpec> {
+    let (name, rest) = path.split_first()?;
+    let sub = subcommands.iter().find(|s| &s.name == name)?;
+    if rest.is_empty() {
+        Some(sub)
+    } else {
+        find_subcommand(&sub.subcommands, rest)
+    }
+}
+
+fn print_names(heading: &str, names: Vec<&str>) {
+    if names.is_empty() {
+        return;
+    }
+    let mut line = names
+        .iter()
+        .take(PREVIEW_NAMES)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > PREVIEW_NAMES {
+        line.push_str(&format!(", ... ({} more)", names.len() - PREVIEW_NAMES));
+    }
+    println!("  {heading}: {line}");
+}
+
+/// Open `spec` as TOML in `$VISUAL` or `$EDITOR` (default `vi`) and parse the
+/// result, reopening the editor after a mistake until the user gives up.
+fn edit_spec(spec: CommandSpec) -> anyhow::Result<CommandSpec> {
+    let file =
+        std::env::temp_dir().join(format!("synapse-{}-{}.toml", spec.name, std::process::id()));
+    std::fs::write(&file, toml::to_string(&spec)?)?;
+    let result = edit_until_valid(&spec, &file);
+    let _ = std::fs::remove_file(&file);
+    result
+}
+
+fn edit_until_valid(spec: &CommandSpec, file: &Path) -> anyhow::Result<CommandSpec> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    loop {
+        // Through the shell so editors configured with arguments (`code -w`) work.
+        let status = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{editor} \"$1\""))
+            .arg("sh")
+            .arg(file)
+            .status()
+            .with_context(|| format!("failed to run {editor}"))?;
+        if !status.success() {
+            anyhow::bail!("{editor} exited with {status}; nothing written");
+        }
+
+        match toml::from_str::<CommandSpec>(&std::fs::read_to_string(file)?) {
+            Ok(edited) if edited.name == spec.name => {
+                return Ok(CommandSpec {
+                    source: spec.source,
+                    ..edited
+                });
+            }
+            Ok(edited) => eprintln!(
+                "The command name must stay '{}' (found '{}')",
+                spec.name, edited.name
+            ),
+            Err(e) => eprintln!("Invalid spec: {e}"),
+        }
+        if prompt("Edit again? [Y/n] ")?.eq_ignore_ascii_case("n") {
+            anyhow::bail!("spec not saved");
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Context;
+
+use crate::llm::audit;
+
+/// Print the last `tail` logged LLM exchanges, oldest first.
+pub(super) fn llm(tail: usize) -> anyhow::Result<()> {
+    let path = audit::audit_log_path().context("cannot determine home directory")?;
+    let exchanges = audit::tail(&path, tail);
+    if exchanges.is_empty() {
+        println!("No LLM exchanges logged (enable with [llm] audit_log = true)");
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    for exchange in exchanges {
+        println!(
+            "--- {} ago, {} at {}",
+            crate::durations::format_secs(now.saturating_sub(exchange.at)),
+            exchange.model,
+            exchange.endpoint
+        );
+        for (role, content) in &exchange.messages {
+            println!("[{role}]\n{content}");
+        }
+        match (&exchange.response, &exchange.error) {
+            (Some(response), _) => println!("[response]\n{response}"),
+            (None, Some(error)) => println!("[error]\n{error}"),
+            (None, None) => {}
+        }
+        println!();
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::io::IsTerminal;
+use std::process::Command;
+
+use crate::config::Config;
+use crate::credentials;
+
+/// Store an API key in the OS keychain, read from stdin (without echo when
+/// typed at a terminal). A provider name (`openai`) is stored under its
+/// variable (`OPENAI_API_KEY`), which is what `[llm] api_key_env` names.
+pub(super) fn set(name: Option<String>) -> anyhow::Result<()> {
+    let name = key_name(name);
+    let secret = read_secret(&format!("{name}: "))?;
+    credentials::set(&name, &secret)?;
+    println!("Stored {name} in the keychain");
+    Ok(())
+}
+
+pub(super) fn remove(name: Option<String>) -> anyhow::Result<()> {
+    let name = key_name(name);
+    if credentials::remove(&name)? {
+        println!("Removed {name} from the keychain");
+    } else {
+        println!("{name} is not in the keychain");
+    }
+    Ok(())
+}
+
+fn key_name(name: Option<String>) -> String {
+    name.map(|name| credentials::key_name(&name))
+        .unwrap_or_else(|| Config::load().llm.api_key_env)
+}
+
+fn read_secret(prompt: &str) -> std::io::Result<String> {
+    let stdin = std::io::stdin();
+    let tty = stdin.is_terminal();
+    if tty {
+        eprint!("{prompt}");
+        let _ = Command::new("stty").arg("-echo").status();
+    }
+    let mut line = String::new();
+    let result = stdin.read_line(&mut line);
+    if tty {
+        let _ = Command::new("stty").arg("echo").status();
+        eprintln!();
+    }
+    result?;
+    Ok(line.trim().to_string())
+}
@@ -0,0 +1,18 @@
+use crate::config::Config;
+use crate::providers::cloud::{list, CloudListing};
+
+pub(super) fn list_resources(listing: String, refresh: bool) -> anyhow::Result<()> {
+    let Some(listing) = CloudListing::from_name(&listing) else {
+        anyhow::bail!(
+            "unknown listing '{listing}' (expected aws-profiles, aws-regions, s3-buckets, gcp-projects, or kube-contexts)"
+        );
+    };
+
+    let config = Config::load();
+    let allow_network = config.spec.cloud_network_completions && !config.is_offline();
+    for name in list(listing, allow_network, refresh) {
+        println!("{name}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::compsys_export::{GeneratedFile, GenerationReport, EXPORT_VERSION};
+use crate::config::Config;
+use crate::spec::{CommandSpec, SpecSource};
+use crate::spec_bundle;
+use crate::spec_store::SpecStore;
+
+use super::scan::resolve_completions_dir;
+
+/// Reconcile the completions directory with the installed tools: drop files
+/// for commands that are gone or now ship their own completion function, and
+/// rewrite files from older export versions or outdated bundles. Project
+/// completions are left to `synapse scan`, which knows the project.
+///
+/// The daily `--background` run only rewrites. It starts from the shell-init
+/// PATH, which lacks tools reached through direnv, virtualenvs or version
+/// manager shims, so removal is left to an explicit `synapse completions sync`.
+pub(super) async fn sync(background: bool, output_dir: Option<PathBuf>) -> anyhow::Result<()> {
+    let stamp = stamp_path();
+    if background && !stamp.as_deref().is_some_and(crate::stamp::sync_due) {
+        return Ok(());
+    }
+    let config = Config::load();
+    let completions_dir = resolve_completions_dir(&config, output_dir);
+    let spec_store = SpecStore::with_completions_dir(config.spec.clone(), completions_dir.clone());
+    let specs_dir = spec_bundle::specs_dir().context("cannot determine home directory")?;
+
+    let mut report = GenerationReport::default();
+    let mut failed = Vec::new();
+    for file in crate::compsys_export::list_generated(&completions_dir) {
+        let stale = file.export_version < EXPORT_VERSION;
+        match file.source {
+            SpecSource::ProjectAuto => {}
+            SpecSource::Imported => match installed_bundle(&specs_dir, &file.command) {
+                None if background => {}
+                None => remove(&file, &mut report.removed)?,
+                Some((spec, bundle_path)) => {
+                    if stale || is_newer(&bundle_path, &file.path) {
+                        crate::compsys_export::write_completion_file(&spec, &completions_dir)?;
+                        report.generated.push(file.command);
+                    }
+                }
+            },
+            SpecSource::Discovered => {
+                if !crate::spec_autogen::is_on_path(&file.command) {
+                    if !background {
+                        remove(&file, &mut report.removed)?;
+                    }
+                } else if crate::zsh_completion::has_completion_outside(
+                    &file.command,
+                    &completions_dir,
+                ) {
+                    if !background {
+                        remove(&file, &mut report.skipped_existing)?;
+                    }
+                } else if stale {
+                    // Keep the old file if the tool no longer describes itself.
+                    match spec_store.discover_spec(&file.command).await {
+                        Some(spec) => {
+                            crate::compsys_export::write_completion_file(&spec, &completions_dir)?;
+                            report.generated.push(file.command);
+                        }
+                        None => failed.push(file.command),
+                    }
+                }
+            }
+        }
+    }
+    if let Some(stamp) = &stamp {
+        crate::stamp::write_stamp(stamp);
+    }
+
+    if background {
+        return Ok(());
+    }
+    println!(
+        "Regenerated {} completions in {}",
+        report.generated.len(),
+        completions_dir.display()
+    );
+    for name in &report.generated {
+        println!("  _{name}");
+    }
+    if !report.removed.is_empty() {
+        println!(
+            "Removed {} completions for uninstalled commands",
+            report.removed.len()
+        );
+        for name in &report.removed {
+            println!("  _{name}");
+        }
+    }
+    if !report.skipped_existing.is_empty() {
+        println!(
+            "Removed {} completions superseded by system completions",
+            report.skipped_existing.len()
+        );
+        for name in &report.skipped_existing {
+            println!("  _{name}");
+        }
+    }
+    for name in &failed {
+        eprintln!("  Could not rediscover {name}; kept the existing file");
+    }
+    Ok(())
+}
+
+fn remove(file: &GeneratedFile, removed: &mut Vec<String>) -> std::io::Result<()> {
+    std::fs::remove_file(&file.path)?;
+    removed.push(file.command.clone());
+    Ok(())
+}
+
+/// The stored bundle for an imported spec: `synapse spec import` writes to
+/// the specs directory, `synapse spec sync` to its `remote/` subdirectory.
+pub(super) fn installed_bundle(specs_dir: &Path, command: &str) -> Option<(CommandSpec, PathBuf)> {
+    let file_name = format!("{command}.toml");
+    [
+        specs_dir.join(&file_name),
+        specs_dir.join("remote").join(&file_name),
+    ]
+    .into_iter()
+    .find_map(|path| {
+        let bundle = spec_bundle::parse(&std::fs::read_to_string(&path).ok()?).ok()?;
+        let mut spec = bundle.spec;
+        spec.source = SpecSource::Imported;
+        Some((spec, path))
+    })
+}
+
+fn is_newer(path: &Path, than: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    matches!((modified(path), modified(than)), (Some(a), Some(b)) if a > b)
+}
+
+fn stamp_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("last-completions-sync"))
+}
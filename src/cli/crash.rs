@@ -0,0 +1,13 @@
+use anyhow::Context;
+
+pub(super) fn last() -> anyhow::Result<()> {
+    let dir = crate::crash::crash_dir().context("cannot determine home directory")?;
+    match crate::crash::last(&dir) {
+        Some((path, report)) => {
+            println!("{}\n", path.display());
+            print!("{report}");
+        }
+        None => println!("No crash reports"),
+    }
+    Ok(())
+}
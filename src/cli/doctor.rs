@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+use super::scan::resolve_completions_dir;
+
+/// Outcome of one health check.
+enum Check {
+    Ok(String),
+    /// A problem, with a hint on how to fix it.
+    Problem(String, String),
+}
+
+/// Check that generated completions can actually load: the completions
+/// directory is on the fpath, the zshrc sources synapse before `compinit`,
+/// and the compinit dump registers every generated command. The plugin's
+/// `synapse doctor` wrapper passes the live `$FPATH` and dump path, and after
+/// `--fix` (which deletes a stale dump) re-runs `compinit` in the shell.
+pub(super) fn doctor(
+    fpath: Option<String>,
+    compdump: Option<PathBuf>,
+    fix: bool,
+) -> anyhow::Result<()> {
+    let config = Config::load();
+    let completions_dir = resolve_completions_dir(&config, None);
+    let zdotdir = std::env::var_os("ZDOTDIR")
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)
+        .unwrap_or_default();
+    let compdump = compdump.unwrap_or_else(|| zdotdir.join(".zcompdump"));
+    let generated: Vec<String> = crate::compsys_export::list_generated(&completions_dir)
+        .into_iter()
+        .map(|file| file.command)
+        .collect();
+
+    let mut checks = vec![
+        check_fpath(
+            fpath.or_else(|| std::env::var("FPATH").ok()).as_deref(),
+            &completions_dir,
+        ),
+        check_zshrc(&zdotdir.join(".zshrc")),
+    ];
+    let dump_check = check_compdump(&compdump, &generated);
+    let stale_dump = matches!(dump_check, Check::Problem(..)) && compdump.exists();
+    checks.push(dump_check);
+
+    let mut problems = 0;
+    println!(
+        "{} generated completions in {}",
+        generated.len(),
+        completions_dir.display()
+    );
+    for check in &checks {
+        match check {
+            Check::Ok(message) => println!("  ok    {message}"),
+            Check::Problem(message, hint) => {
+                problems += 1;
+                println!("  FAIL  {message}");
+                println!("        {hint}");
+            }
+        }
+    }
+
+    if fix && stale_dump {
+        // Also drop the compiled dump, or zsh keeps loading the stale one.
+        let mut zwc = compdump.clone().into_os_string();
+        zwc.push(".zwc");
+        let _ = std::fs::remove_file(&zwc);
+        std::fs::remove_file(&compdump)?;
+        problems -= 1;
+        println!("Removed {}; compinit rebuilds it", compdump.display());
+    }
+
+    if problems > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn check_fpath(fpath: Option<&str>, completions_dir: &Path) -> Check {
+    let Some(fpath) = fpath.filter(|f| !f.is_empty()) else {
+        return Check::Problem(
+            "fpath unknown".to_string(),
+            "Run `synapse doctor` from an interactive zsh with the plugin loaded".to_string(),
+        );
+    };
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let target = canonical(completions_dir);
+    if std::env::split_paths(fpath).any(|dir| canonical(&dir) == target) {
+        Check::Ok(format!("{} is on fpath", completions_dir.display()))
+    } else {
+        Check::Problem(
+            format!("{} is not on fpath", completions_dir.display()),
+            "Load synapse with `eval \"$(synapse)\"` (see `synapse install`)".to_string(),
+        )
+    }
+}
+
+fn check_zshrc(zshrc: &Path) -> Check {
+    let Ok(contents) = std::fs::read_to_string(zshrc) else {
+        return Check::Ok(format!("{} not found; skipped", zshrc.display()));
+    };
+    let init_line = r#"eval "$(synapse)""#;
+    let Some(init) = contents.find(init_line) else {
+        return Check::Problem(
+            format!("{} does not load synapse", zshrc.display()),
+            "Run `synapse install`".to_string(),
+        );
+    };
+    match super::shell::find_compinit_line_start(&contents) {
+        Some(compinit) if compinit < init => Check::Problem(
+            format!("{} runs compinit before loading synapse", zshrc.display()),
+            format!("Move `{init_line}` above the compinit line"),
+        ),
+        _ => Check::Ok(format!("{} loads synapse before compinit", zshrc.display())),
+    }
+}
+
+fn check_compdump(compdump: &Path, generated: &[String]) -> Check {
+    let Ok(contents) = std::fs::read_to_string(compdump) else {
+        return Check::Ok(format!("{} not present yet", compdump.display()));
+    };
+    let missing = generated
+        .iter()
+        .filter(|command| !contents.contains(&format!("'{command}' '_")))
+        .count();
+    // Rewritten files need no new dump (functions autoload by name); only
+    // commands compinit has never seen are a problem.
+    if missing > 0 {
+        Check::Problem(
+            format!(
+                "{} is stale ({missing} generated completions not registered)",
+                compdump.display()
+            ),
+            "Run `synapse doctor --fix` to rebuild it".to_string(),
+        )
+    } else {
+        Check::Ok(format!("{} is up to date", compdump.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_compdump_detects_unregistered_completions() {
+        let dir = tempfile::tempdir().unwrap();
+        let dump = dir.path().join(".zcompdump");
+        std::fs::write(&dump, "_comps=(\n'deployctl' '_deployctl'\n)\n").unwrap();
+
+        let registered = ["deployctl".to_string()];
+        assert!(matches!(check_compdump(&dump, &registered), Check::Ok(_)));
+        let unregistered = ["deployctl".to_string(), "kubectx".to_string()];
+        assert!(matches!(
+            check_compdump(&dump, &unregistered),
+            Check::Problem(..)
+        ));
+    }
+
+    #[test]
+    fn test_check_zshrc_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let zshrc = dir.path().join(".zshrc");
+        std::fs::write(
+            &zshrc,
+            "autoload -Uz compinit && compinit\neval \"$(synapse)\"\n",
+        )
+        .unwrap();
+        assert!(matches!(check_zshrc(&zshrc), Check::Problem(..)));
+        std::fs::write(
+            &zshrc,
+            "eval \"$(synapse)\"\nautoload -Uz compinit && compinit\n",
+        )
+        .unwrap();
+        assert!(matches!(check_zshrc(&zshrc), Check::Ok(_)));
+    }
+}
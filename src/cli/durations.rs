@@ -0,0 +1,27 @@
+use crate::config::Config;
+use crate::durations;
+
+pub(super) fn record(command: String, secs: u64) -> anyhow::Result<()> {
+    let config = Config::load();
+    if !config.memory.durations {
+        return Ok(());
+    }
+    if let Some(path) = durations::durations_path() {
+        durations::record(&path, &command, secs)?;
+    }
+    Ok(())
+}
+
+pub(super) fn list() -> anyhow::Result<()> {
+    let Some(path) = durations::durations_path() else {
+        return Ok(());
+    };
+    for entry in durations::load(&path) {
+        println!(
+            "{}\t{}",
+            durations::format_secs(entry.last_secs),
+            entry.command
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::config::Config;
+use crate::nl_examples;
+
+pub(super) fn record(query: String, command: String) -> anyhow::Result<()> {
+    let config = Config::load();
+    if config.llm.few_shot_examples == 0 {
+        return Ok(());
+    }
+    if let Some(path) = nl_examples::examples_path() {
+        nl_examples::record(&path, &query, &command)?;
+    }
+    Ok(())
+}
+
+pub(super) fn list() -> anyhow::Result<()> {
+    let Some(path) = nl_examples::examples_path() else {
+        return Ok(());
+    };
+    for example in nl_examples::load(&path) {
+        println!("{}\t{}", example.query, example.command);
+    }
+    Ok(())
+}
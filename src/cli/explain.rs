@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::explain;
+use crate::spec_store::SpecStore;
+
+/// Print a one-line summary of `command`, or nothing if none is available.
+pub(super) async fn explain_command(command: String, cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let config = Config::load();
+    let command = command.trim().to_string();
+    let Some(program) = command.split_whitespace().next() else {
+        return Ok(());
+    };
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()?,
+    };
+
+    let spec_store = SpecStore::new(config.spec.clone());
+    if let Some(spec) = spec_store.lookup_with_system_fallback(program, &cwd).await {
+        if let Some(summary) = explain::summarize_from_spec(&spec, &command) {
+            println!("{summary}");
+            return Ok(());
+        }
+    }
+
+    let cache = explain::cache_path();
+    if let Some(summary) = cache.as_deref().and_then(|p| explain::cached(p, &command)) {
+        println!("{summary}");
+        return Ok(());
+    }
+
+    if config.llm_blocked_offline() {
+        return Ok(());
+    }
+    let Some(mut llm_client) = crate::llm::LlmClient::from_config(&config.llm) else {
+        return Ok(());
+    };
+    llm_client.auto_detect_model().await;
+
+    let scrubbed = crate::llm::scrub_secrets(&command);
+    if let Ok(summary) = llm_client
+        .summarize_command(&scrubbed, config.language())
+        .await
+    {
+        if let Some(path) = cache {
+            let _ = explain::store(&path, &command, &summary);
+        }
+        println!("{summary}");
+    }
+    Ok(())
+}
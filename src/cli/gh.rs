@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::providers::gh::{list, GhListing};
+
+/// Print `value<TAB>title` lines, for generators with `format = "tsv"`.
+pub(super) fn list_items(
+    listing: String,
+    cwd: Option<PathBuf>,
+    refresh: bool,
+) -> anyhow::Result<()> {
+    let Some(listing) = GhListing::from_name(&listing) else {
+        anyhow::bail!("unknown listing '{listing}' (expected prs, pr-branches, or issues)");
+    };
+    let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
+    let offline = Config::load().is_offline();
+
+    for (value, title) in list(listing, &cwd, refresh, offline) {
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        println!("{value}\t{title}");
+    }
+
+    Ok(())
+}
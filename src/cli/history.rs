@@ -0,0 +1,43 @@
+use anyhow::Context;
+use regex::Regex;
+
+use crate::records::{self, Filter};
+
+pub(super) fn export(format: String) -> anyhow::Result<()> {
+    if format != "jsonl" {
+        anyhow::bail!("unknown format '{format}' (expected jsonl)");
+    }
+    let dir = records::data_dir().context("cannot determine home directory")?;
+    records::export(&dir, &mut std::io::stdout().lock())?;
+    Ok(())
+}
+
+pub(super) fn forget(matching: Option<String>, before: Option<String>) -> anyhow::Result<()> {
+    if matching.is_none() && before.is_none() {
+        anyhow::bail!("pass --matching <regex>, --before <YYYY-MM-DD>, or both");
+    }
+    let filter = Filter {
+        matching: matching
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("invalid --matching pattern")?,
+        before: match before.as_deref() {
+            Some(date) => Some(
+                records::parse_date(date)
+                    .with_context(|| format!("invalid date '{date}' (expected YYYY-MM-DD)"))?,
+            ),
+            None => None,
+        },
+    };
+
+    let dir = records::data_dir().context("cannot determine home directory")?;
+    let removed = records::forget(&dir, &filter)?;
+    if removed.is_empty() {
+        println!("No matching entries");
+    }
+    for (store, count) in removed {
+        println!("Removed {count} from {store}");
+    }
+    Ok(())
+}
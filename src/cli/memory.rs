@@ -0,0 +1,56 @@
+use crate::config::Config;
+use crate::memory::{self, EntityKind};
+use crate::spec_store::SpecStore;
+
+/// Maximum entities printed by `synapse memory list`.
+const LIST_LIMIT: usize = 50;
+
+/// Record entities, and the values passed to flags the command's spec says
+/// take an argument.
+pub(super) async fn record(command: String) -> anyhow::Result<()> {
+    let config = Config::load();
+    if !config.memory.enabled {
+        return Ok(());
+    }
+    if let Some(path) = memory::memory_path() {
+        memory::record(&path, &command)?;
+    }
+
+    let (Some(program), Some(path)) = (
+        command.split_whitespace().next(),
+        crate::flag_values::flag_values_path(),
+    ) else {
+        return Ok(());
+    };
+    let cwd = std::env::current_dir()?;
+    let spec_store = SpecStore::new(config.spec.clone());
+    if let Some(spec) = spec_store.lookup_with_system_fallback(program, &cwd).await {
+        let pairs = crate::flag_values::extract(&spec, command.trim());
+        crate::flag_values::record(&path, &spec.name, &pairs)?;
+    }
+    Ok(())
+}
+
+pub(super) fn list(kind: Option<String>) -> anyhow::Result<()> {
+    let kind = match kind.as_deref() {
+        Some(name) => Some(EntityKind::from_name(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown entity kind '{name}' (expected branch, container, path, or host)"
+            )
+        })?),
+        None => None,
+    };
+    let Some(path) = memory::memory_path() else {
+        return Ok(());
+    };
+
+    for entity in memory::recent(&path, kind, LIST_LIMIT) {
+        // With a kind filter, print bare values so the output works as a generator.
+        if kind.is_some() {
+            println!("{}", entity.value);
+        } else {
+            println!("{}\t{}", entity.kind.name(), entity.value);
+        }
+    }
+    Ok(())
+}
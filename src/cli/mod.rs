@@ -4,11 +4,33 @@ use std::path::PathBuf;
 use clap::{CommandFactory, Parser, Subcommand};
 
 mod add;
+mod audit;
+mod auth;
+mod cloud;
+mod completions;
+mod crash;
+mod doctor;
+mod durations;
+mod examples;
+mod explain;
+mod gh;
+mod history;
+mod memory;
+mod pin;
+mod policy;
+mod project_values;
+mod registry;
 mod run_generator;
 mod scan;
 pub mod shell;
+mod snippet;
+mod spec;
+mod status;
+mod suggest;
+mod terraform;
 mod translate;
 pub mod update;
+mod validate;
 
 #[derive(Parser)]
 #[command(
@@ -25,6 +47,11 @@ struct Cli {
 enum Commands {
     /// Add synapse to your ~/.zshrc
     Install,
+    /// Store or remove an API key in the OS keychain
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
     /// Scan project files in cwd and write completion files (Makefile, package.json, etc.)
     Scan {
         /// Output directory (default: ~/.synapse/completions/)
@@ -38,6 +65,10 @@ enum Commands {
         /// Generate for all commands, even those with existing compsys functions
         #[arg(long)]
         no_gap_check: bool,
+
+        /// Keep running and rescan whenever the project's spec files change
+        #[arg(long)]
+        watch: bool,
     },
     /// Run a generator command with timeout, split, and prefix stripping
     RunGenerator {
@@ -55,17 +86,195 @@ enum Commands {
         /// Split output on this delimiter (default: newline)
         #[arg(long)]
         split_on: Option<String>,
+
+        /// Item format: lines, or tsv (`value<TAB>description`, printed as
+        /// `value:description` for `_describe`)
+        #[arg(long, default_value = "lines")]
+        format: String,
     },
     /// Add completions for a command by running its --help or completion generator
     Add {
-        /// Command name to add
-        command: String,
+        /// Command to add, or a subcommand path such as `aws s3` (names or globs
+        /// with --from-path)
+        #[arg(required = true, num_args = 1..)]
+        command: Vec<String>,
+
+        /// Edit the discovered spec in $EDITOR before writing it
+        #[arg(long, conflicts_with = "from_path")]
+        edit: bool,
+
+        /// Treat each argument as a command name or glob (`kube*`) matched
+        /// against PATH, add every match, and print a summary
+        #[arg(long)]
+        from_path: bool,
 
         /// Output directory (default: ~/.synapse/completions/)
         #[arg(long)]
         output_dir: Option<PathBuf>,
     },
+    /// List cloud profiles and resources (AWS, GCP, Kubernetes)
+    Cloud {
+        /// What to list: aws-profiles, aws-regions, s3-buckets, gcp-projects, or kube-contexts
+        listing: String,
+
+        /// Rebuild the cached listing instead of reusing it
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Maintain the generated completions directory
+    Completions {
+        #[command(subcommand)]
+        action: CompletionsAction,
+    },
+    /// Check that generated completions are on the fpath and registered with compinit
+    Doctor {
+        /// The shell's $FPATH (passed by the plugin; defaults to the environment)
+        #[arg(long)]
+        fpath: Option<String>,
+
+        /// compinit dump file (default: ${ZDOTDIR:-~}/.zcompdump)
+        #[arg(long)]
+        compdump: Option<PathBuf>,
+
+        /// Delete a stale dump so compinit rebuilds it
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Show crash reports written when synapse panics
+    Crash {
+        #[command(subcommand)]
+        action: CrashAction,
+    },
+    /// Inspect what synapse has sent to LLM endpoints
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+    /// Export or purge the commands synapse has stored (examples, durations, memory, ...)
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Record or list entities (branches, containers, paths, hosts) from executed commands
+    Memory {
+        #[command(subcommand)]
+        action: MemoryAction,
+    },
+    /// Record or list how long slow commands took
+    Durations {
+        #[command(subcommand)]
+        action: DurationsAction,
+    },
+    /// Few-shot examples mined from accepted NL translations
+    Examples {
+        #[command(subcommand)]
+        action: ExamplesAction,
+    },
+    /// Print a one-line plain-English summary of a command
+    Explain {
+        /// The command line to summarize
+        command: String,
+
+        /// Working directory used to resolve project specs
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// List open pull requests or issues of the current GitHub repository (via `gh`)
+    Gh {
+        /// What to list: prs, pr-branches, or issues
+        listing: String,
+
+        /// Working directory inside the repository
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Query `gh` again instead of reusing the cached listing
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Inspect the suggestion security policy
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+    },
+    /// List package names from a local package-manager cache (npm, cargo, pip, brew)
+    Registry {
+        /// Ecosystem to list: npm, cargo, pip, brew, or brew-installed
+        ecosystem: String,
+
+        /// Rebuild the cached listing instead of reusing it
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Manage abbreviations expanded by the plugin (e.g. `kgp` -> `kubectl get pods`)
+    Snippet {
+        #[command(subcommand)]
+        action: SnippetAction,
+    },
+    /// Pin commands for the current project (kept in .synapse.toml)
+    Pin {
+        #[command(subcommand)]
+        action: PinAction,
+    },
+    /// Export, import, or sync shareable spec bundles
+    Spec {
+        #[command(subcommand)]
+        action: SpecAction,
+    },
+    /// List Makefile targets with their `##` descriptions (called by the make completion)
+    MakeTargets {
+        /// Directory containing the Makefile
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// List package.json scripts with their commands (called by the npm/yarn/pnpm/bun completions)
+    PackageScripts {
+        /// Directory containing package.json
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// List project-local executables (./scripts/*, bin/*, package.json bin) for command completion
+    LocalScripts {
+        /// Project directory
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// List terraform resource addresses from local state and *.tfplan.json files
+    TerraformAddresses {
+        /// Terraform working directory
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// Check a command line against its spec and print warnings (called by the plugin)
+    Validate {
+        /// The command line about to run
+        command: String,
+
+        /// Working directory used to resolve project specs
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// List spec-based completions for a partially typed command line
+    Suggest {
+        /// The command line so far (a trailing space completes the next word)
+        buffer: String,
+
+        /// Working directory used to resolve project specs
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Print the candidates as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show version, config, update status, completions, LLM, and cache sizes
+    Status {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Check for updates or self-update the synapse binary
+    #[command(alias = "self-update")]
     Update {
         /// Only check and cache the latest version (for background use)
         #[arg(long)]
@@ -76,9 +285,9 @@ enum Commands {
         /// The natural language query
         query: String,
 
-        /// Working directory
+        /// Working directory (default: current directory)
         #[arg(long)]
-        cwd: PathBuf,
+        cwd: Option<PathBuf>,
 
         /// Recent commands for context
         #[arg(long)]
@@ -87,19 +296,255 @@ enum Commands {
         /// Environment hints (KEY=VAL)
         #[arg(long)]
         env_hint: Vec<String>,
+
+        /// Run the chosen command after confirmation
+        #[arg(long, conflicts_with = "copy")]
+        execute: bool,
+
+        /// Copy the chosen command to the clipboard
+        #[arg(long)]
+        copy: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CompletionsAction {
+    /// Remove completions for uninstalled or now-covered commands and regenerate stale ones
+    Sync {
+        /// Sync silently, at most daily, and only regenerate (never remove)
+        #[arg(long)]
+        background: bool,
+
+        /// Output directory (default: ~/.synapse/completions/)
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthAction {
+    /// Store a key, read from stdin (prompted without echo at a terminal)
+    Set {
+        /// Key name or provider, e.g. `openai` for OPENAI_API_KEY (default: the `[llm] api_key_env` name)
+        name: Option<String>,
+    },
+    /// Remove a stored key
+    Remove {
+        /// Key name or provider, e.g. `openai` for OPENAI_API_KEY (default: the `[llm] api_key_env` name)
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DurationsAction {
+    /// Record a command's run time (called by the plugin)
+    Record {
+        /// The command that was executed
+        command: String,
+        /// Wall-clock seconds it took
+        #[arg(long)]
+        secs: u64,
+    },
+    /// List recorded run times, most recent first
+    List,
+}
+
+#[derive(Subcommand)]
+enum CrashAction {
+    /// Print the most recent crash report
+    Last,
+}
+
+#[derive(Subcommand)]
+enum AuditAction {
+    /// Show logged prompts and raw responses (requires `[llm] audit_log`)
+    Llm {
+        /// Number of most recent exchanges to show
+        #[arg(long, default_value_t = 10)]
+        tail: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// Print every stored entry, one JSON object per line tagged with its store
+    Export {
+        /// Output format (only jsonl)
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+    },
+    /// Remove stored entries matching a regex and/or recorded before a date
+    Forget {
+        /// Regex matched against stored commands, queries, and values
+        #[arg(long)]
+        matching: Option<String>,
+        /// Only entries recorded before this date (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        before: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExamplesAction {
+    /// Record an NL query whose translation ran successfully (called by the plugin)
+    Record {
+        /// The natural language query
+        query: String,
+        /// The command that was executed
+        command: String,
+    },
+    /// List recorded examples, newest first
+    List,
+}
+
+#[derive(Subcommand)]
+enum MemoryAction {
+    /// Extract entities and flag values from an executed command (called by the plugin)
+    Record {
+        /// The command line that was executed
+        command: String,
+    },
+    /// List remembered entities, newest first
+    List {
+        /// Only list one kind: branch, container, path, or host
+        #[arg(long)]
+        kind: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnippetAction {
+    /// Add or replace a snippet
+    Add {
+        /// Single-word trigger typed at the prompt
+        trigger: String,
+        /// Command line it expands to; `<placeholders>` mark fields to fill
+        expansion: String,
+    },
+    /// List snippets (personal and team)
+    List {
+        /// Print as zsh source for the plugin
+        #[arg(long)]
+        zsh: bool,
+    },
+    /// Remove a snippet
+    Rm {
+        /// Trigger to remove
+        trigger: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PinAction {
+    /// Pin a command for this project
+    Add {
+        /// Command line to pin
+        command: String,
+        /// Project directory (default: cwd)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// Unpin a command
+    #[command(alias = "rm")]
+    Remove {
+        /// Pinned command line
+        command: String,
+        /// Project directory (default: cwd)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// List this project's pins
+    List {
+        /// Print in the `translate` TSV list format for the plugin
+        #[arg(long)]
+        tsv: bool,
+        /// Project directory (default: cwd)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SpecAction {
+    /// Package a command's spec as a TOML bundle
+    Export {
+        /// Command name to export
+        command: String,
+
+        /// Write the bundle to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Working directory used to resolve project specs
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    /// Install a spec bundle from a file or URL
+    Import {
+        /// Bundle file path or https URL
+        source: String,
+
+        /// Keep the bundle's generator commands (they run at completion time)
+        #[arg(long)]
+        trust_generators: bool,
+
+        /// Output directory (default: ~/.synapse/completions/)
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+    },
+    /// Pull bundles from the configured remote spec sources
+    Sync {
+        /// Sync silently, at most daily, and only regenerate (never remove)
+        #[arg(long)]
+        background: bool,
+
+        /// Output directory (default: ~/.synapse/completions/)
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PolicyAction {
+    /// Show whether a command would be allowed, and which rule decided
+    Test {
+        /// The command line to evaluate
+        command: String,
+
+        /// Suggestion source the rule should see
+        #[arg(long, default_value = "llm")]
+        source: String,
+
+        /// Working directory to evaluate directory rules against
+        #[arg(long)]
+        cwd: Option<PathBuf>,
     },
 }
 
 pub async fn run() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let subcommand = std::env::args()
+        .nth(1)
+        .filter(|arg| Cli::command().find_subcommand(arg).is_some());
+    crate::crash::install_hook(subcommand);
 
     match cli.command {
         Some(Commands::Add {
             command,
+            edit,
+            from_path,
             output_dir,
         }) => {
-            add::add_command(command, output_dir).await?;
+            if from_path {
+                add::add_from_path(command, output_dir).await?;
+            } else {
+                add::add_command(command, edit, output_dir).await?;
+            }
         }
+        Some(Commands::Auth { action }) => match action {
+            AuthAction::Set { name } => auth::set(name)?,
+            AuthAction::Remove { name } => auth::remove(name)?,
+        },
         Some(Commands::Install) => {
             shell::setup_shell_rc("~/.zshrc")?;
         }
@@ -107,16 +552,123 @@ pub async fn run() -> anyhow::Result<()> {
             output_dir,
             force,
             no_gap_check,
+            watch,
         }) => {
-            scan::scan_project(output_dir, force, no_gap_check).await?;
+            scan::scan_project(output_dir, force, no_gap_check, watch).await?;
         }
         Some(Commands::RunGenerator {
             command,
             cwd,
             strip_prefix,
             split_on,
+            format,
+        }) => {
+            run_generator::run_generator(command, cwd, strip_prefix, split_on, format).await?;
+        }
+        Some(Commands::Cloud { listing, refresh }) => {
+            cloud::list_resources(listing, refresh)?;
+        }
+        Some(Commands::Doctor {
+            fpath,
+            compdump,
+            fix,
+        }) => {
+            doctor::doctor(fpath, compdump, fix)?;
+        }
+        Some(Commands::Completions { action }) => match action {
+            CompletionsAction::Sync {
+                background,
+                output_dir,
+            } => completions::sync(background, output_dir).await?,
+        },
+        Some(Commands::Durations { action }) => match action {
+            DurationsAction::Record { command, secs } => durations::record(command, secs)?,
+            DurationsAction::List => durations::list()?,
+        },
+        Some(Commands::Examples { action }) => match action {
+            ExamplesAction::Record { query, command } => examples::record(query, command)?,
+            ExamplesAction::List => examples::list()?,
+        },
+        Some(Commands::Explain { command, cwd }) => {
+            explain::explain_command(command, cwd).await?;
+        }
+        Some(Commands::Gh {
+            listing,
+            cwd,
+            refresh,
         }) => {
-            run_generator::run_generator(command, cwd, strip_prefix, split_on).await?;
+            gh::list_items(listing, cwd, refresh)?;
+        }
+        Some(Commands::Crash { action }) => match action {
+            CrashAction::Last => crash::last()?,
+        },
+        Some(Commands::Audit { action }) => match action {
+            AuditAction::Llm { tail } => audit::llm(tail)?,
+        },
+        Some(Commands::History { action }) => match action {
+            HistoryAction::Export { format } => history::export(format)?,
+            HistoryAction::Forget { matching, before } => history::forget(matching, before)?,
+        },
+        Some(Commands::Memory { action }) => match action {
+            MemoryAction::Record { command } => memory::record(command).await?,
+            MemoryAction::List { kind } => memory::list(kind)?,
+        },
+        Some(Commands::Policy { action }) => match action {
+            PolicyAction::Test {
+                command,
+                source,
+                cwd,
+            } => policy::test(command, source, cwd)?,
+        },
+        Some(Commands::Registry { ecosystem, refresh }) => {
+            registry::list_packages(ecosystem, refresh)?;
+        }
+        Some(Commands::Snippet { action }) => match action {
+            SnippetAction::Add { trigger, expansion } => snippet::add(trigger, expansion)?,
+            SnippetAction::List { zsh } => snippet::list(zsh)?,
+            SnippetAction::Rm { trigger } => snippet::remove(trigger)?,
+        },
+        Some(Commands::Pin { action }) => match action {
+            PinAction::Add { command, cwd } => pin::add(command, cwd)?,
+            PinAction::Remove { command, cwd } => pin::remove(command, cwd)?,
+            PinAction::List { tsv, cwd } => pin::list(tsv, cwd)?,
+        },
+        Some(Commands::Spec { action }) => match action {
+            SpecAction::Export {
+                command,
+                output,
+                cwd,
+            } => spec::export(command, output, cwd).await?,
+            SpecAction::Import {
+                source,
+                trust_generators,
+                output_dir,
+            } => spec::import(source, trust_generators, output_dir).await?,
+            SpecAction::Sync {
+                background,
+                output_dir,
+            } => spec::sync(background, output_dir).await?,
+        },
+        Some(Commands::Status { json }) => {
+            status::status(json).await?;
+        }
+        Some(Commands::MakeTargets { cwd }) => {
+            project_values::make_targets(cwd)?;
+        }
+        Some(Commands::PackageScripts { cwd }) => {
+            project_values::package_scripts(cwd)?;
+        }
+        Some(Commands::LocalScripts { cwd }) => {
+            project_values::local_scripts(cwd)?;
+        }
+        Some(Commands::TerraformAddresses { cwd }) => {
+            terraform::list_addresses(cwd)?;
+        }
+        Some(Commands::Validate { command, cwd }) => {
+            validate::validate_command(command, cwd).await?;
+        }
+        Some(Commands::Suggest { buffer, cwd, json }) => {
+            suggest::suggest(buffer, cwd, json).await?;
         }
         Some(Commands::Update { check }) => {
             update::run(check).await?;
@@ -126,8 +678,23 @@ pub async fn run() -> anyhow::Result<()> {
             cwd,
             recent_command,
             env_hint,
+            execute,
+            copy,
         }) => {
-            translate::translate(query, cwd, recent_command, env_hint).await?;
+            let output = if execute {
+                translate::Output::Execute
+            } else if copy {
+                translate::Output::Copy
+            } else if std::io::stdout().is_terminal() {
+                translate::Output::Print
+            } else {
+                translate::Output::Tsv
+            };
+            let cwd = match cwd {
+                Some(cwd) => cwd,
+                None => std::env::current_dir()?,
+            };
+            translate::translate(query, cwd, recent_command, env_hint, output).await?;
         }
         None => {
             if std::io::stdout().is_terminal() {
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::pins;
+
+use super::translate::{apply_policy, format_suggestion_list, localize_risks};
+
+fn resolve_cwd(cwd: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    match cwd {
+        Some(cwd) => Ok(cwd),
+        None => Ok(std::env::current_dir()?),
+    }
+}
+
+pub(super) fn add(command: String, cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let config = Config::load();
+    let cwd = resolve_cwd(cwd)?;
+    match pins::add(&cwd, command.trim(), config.spec.scan_depth)? {
+        Some(path) => println!("Pinned '{}' in {}", command.trim(), path.display()),
+        None => println!("'{}' is already pinned", command.trim()),
+    }
+    Ok(())
+}
+
+pub(super) fn remove(command: String, cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let cwd = resolve_cwd(cwd)?;
+    match pins::remove(&cwd, command.trim())? {
+        Some(path) => println!("Unpinned '{}' in {}", command.trim(), path.display()),
+        None => println!("'{}' is not pinned", command.trim()),
+    }
+    Ok(())
+}
+
+/// Print pins one per line, or with `--tsv` in the `list` format of
+/// `synapse translate` so the plugin can show them in its dropdown. Pins
+/// come from a file in the repository, so the dropdown gets them only after
+/// the same policy and risk checks as NL suggestions (source `pin`).
+pub(super) fn list(tsv: bool, cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let cwd = resolve_cwd(cwd)?;
+    let pins = pins::load(&cwd);
+    if !tsv {
+        for pin in &pins {
+            println!("{pin}");
+        }
+        return Ok(());
+    }
+
+    let config = Config::load();
+    let items = pins
+        .into_iter()
+        .map(|command| crate::llm::NlTranslationItem {
+            risk: crate::llm::assess_risk(&command),
+            command,
+        })
+        .collect();
+    let mut items = apply_policy(items, "pin", &cwd, &config);
+    localize_risks(&mut items, config.language());
+    let durations = crate::durations::durations_path()
+        .map(|path| crate::durations::load(&path))
+        .unwrap_or_default();
+    println!(
+        "{}",
+        format_suggestion_list(&items, "pin", &cwd, &durations)
+    );
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::security::PolicyEngine;
+
+pub(super) fn test(command: String, source: String, cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let config = Config::load();
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()?,
+    };
+
+    let decision = PolicyEngine::new(&config.security).evaluate(&command, &source, &cwd);
+    let verdict = if decision.allowed { "allow" } else { "deny" };
+    match decision.rule {
+        Some(rule) => println!("{verdict}\t{rule}"),
+        None => println!("{verdict}\t(no matching rule)"),
+    }
+    Ok(())
+}
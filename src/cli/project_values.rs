@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use crate::spec_autogen;
+
+/// Print the Makefile targets in `cwd` as `target<TAB>description` lines for
+/// the `_make` generator. Falls back to make's own database when the Makefile
+/// yields no targets the parser understands.
+pub(super) fn make_targets(cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
+    let Some(makefile) = spec_autogen::find_makefile(&cwd) else {
+        return Ok(());
+    };
+    let content = std::fs::read_to_string(makefile).unwrap_or_default();
+    let targets = spec_autogen::parse_makefile_targets(&content);
+    if targets.is_empty() {
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(spec_autogen::MAKE_QP_TARGETS)
+            .current_dir(&cwd)
+            .stdin(std::process::Stdio::null())
+            .output()?;
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+        return Ok(());
+    }
+
+    for target in targets {
+        match target.description {
+            Some(description) => println!("{}\t{description}", target.value),
+            None => println!("{}", target.value),
+        }
+    }
+    Ok(())
+}
+
+/// Print the project-local executables in `cwd` as typed at the prompt
+/// (`./scripts/deploy.sh`), for command-position completion.
+pub(super) fn local_scripts(cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
+    for script in spec_autogen::project_scripts(&cwd) {
+        println!("{script}");
+    }
+    Ok(())
+}
+
+/// Print the package.json scripts in `cwd` as `script<TAB>command` lines for
+/// the package manager generators.
+pub(super) fn package_scripts(cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
+    let content = std::fs::read_to_string(cwd.join("package.json")).unwrap_or_default();
+    for script in spec_autogen::parse_package_scripts(&content) {
+        match script.description {
+            Some(description) => println!("{}\t{description}", script.value),
+            None => println!("{}", script.value),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::providers::registry::{cached_packages, Ecosystem};
+
+pub(super) fn list_packages(ecosystem: String, refresh: bool) -> anyhow::Result<()> {
+    let Some(ecosystem) = Ecosystem::from_name(&ecosystem) else {
+        anyhow::bail!(
+            "unknown ecosystem '{ecosystem}' (expected npm, cargo, pip, brew, or brew-installed)"
+        );
+    };
+
+    for name in cached_packages(ecosystem, refresh) {
+        println!("{name}");
+    }
+
+    Ok(())
+}
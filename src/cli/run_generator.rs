@@ -3,12 +3,20 @@ use std::time::Duration;
 
 use tokio::process::Command;
 
+use crate::spec::GeneratorFormat;
+
+/// Print one value per line; with the TSV format, `value:description`
+/// entries ready for `_describe`.
 pub(super) async fn run_generator(
     command: String,
     cwd: Option<PathBuf>,
     strip_prefix: Option<String>,
     split_on: Option<String>,
+    format: String,
 ) -> anyhow::Result<()> {
+    let Some(format) = GeneratorFormat::from_name(&format) else {
+        anyhow::bail!("unknown format '{format}' (expected lines or tsv)");
+    };
     let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
     let split_on = split_on.unwrap_or_else(|| "\n".to_string());
     let timeout = Duration::from_millis(crate::config::GENERATOR_TIMEOUT_MS);
@@ -40,10 +48,39 @@ pub(super) async fn run_generator(
                 item = stripped.to_string();
             }
         }
-        if !item.is_empty() {
-            println!("{item}");
+        if item.is_empty() {
+            continue;
+        }
+        match format {
+            GeneratorFormat::Lines => println!("{item}"),
+            GeneratorFormat::Tsv => println!("{}", describe_entry(&item)),
         }
     }
 
     Ok(())
 }
+
+/// `value<TAB>description` as a `_describe` entry, escaping colons in the
+/// value so they are not read as the separator.
+fn describe_entry(item: &str) -> String {
+    let (value, description) = item.split_once('\t').unwrap_or((item, ""));
+    let value = value.trim().replace('\\', "\\\\").replace(':', "\\:");
+    let description = description.trim().replace('\t', " ");
+    if description.is_empty() {
+        value
+    } else {
+        format!("{value}:{description}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_entry() {
+        assert_eq!(describe_entry("main\tFix login"), "main:Fix login");
+        assert_eq!(describe_entry("build:prod\t"), "build\\:prod");
+        assert_eq!(describe_entry("a\tb\tc"), "a:b c");
+    }
+}
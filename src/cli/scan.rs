@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use crate::config::Config;
 use crate::spec_store::SpecStore;
@@ -18,10 +20,14 @@ pub(crate) fn resolve_completions_dir(config: &Config, output_dir: Option<PathBu
     })
 }
 
+/// How often `scan --watch` checks the project files for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
 pub(super) async fn scan_project(
     output_dir: Option<PathBuf>,
     force: bool,
     no_gap_check: bool,
+    watch: bool,
 ) -> anyhow::Result<()> {
     let config = Config::load();
     let output = resolve_completions_dir(&config, output_dir);
@@ -40,16 +46,59 @@ pub(super) async fn scan_project(
     }
 
     let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+    scan_once(&config, &cwd, &output, &existing, gap_only, force, false).await?;
+    if !watch {
+        return Ok(());
+    }
+
+    println!("Watching {} for changes (Ctrl-C to stop)", cwd.display());
+    let mut last = fingerprint(&cwd);
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+        let current = fingerprint(&cwd);
+        if current == last {
+            continue;
+        }
+        last = current;
+        // A failed pass (say, a half-saved file) should not end the watch.
+        // The completions came from this directory, so a pass that finds
+        // no project any more removes them.
+        if let Err(e) = scan_once(&config, &cwd, &output, &existing, gap_only, false, true).await {
+            eprintln!("Scan failed: {e}");
+        }
+    }
+}
+
+/// Generate completions for the project specs in `cwd` and report them.
+/// Completions of earlier projects are replaced, but kept when `cwd` holds
+/// no project unless `prune_outside_project` is set.
+async fn scan_once(
+    config: &Config,
+    cwd: &Path,
+    output: &Path,
+    existing: &HashSet<String>,
+    gap_only: bool,
+    force: bool,
+    prune_outside_project: bool,
+) -> anyhow::Result<()> {
+    // A fresh store each pass, so the project spec cache never serves a
+    // spec from before the change.
     let spec_store = SpecStore::new(config.spec.clone());
-    let project_specs: Vec<_> = spec_store.lookup_all_project_specs(&cwd).await;
+    let mut project_specs: Vec<_> = spec_store.lookup_all_project_specs(cwd).await;
+    let mut in_project = !project_specs.is_empty();
+    project_specs.retain(|spec| {
+        !crate::spec_autogen::DEFERRING_SPECS.contains(&spec.name.as_str())
+            || !crate::zsh_completion::has_completion_outside(&spec.name, output)
+    });
+    project_specs.extend(spec_store.discover_project_scripts(cwd).await);
+    in_project |= !project_specs.is_empty();
 
     let mut report =
-        crate::compsys_export::generate_all(&project_specs, &existing, &output, gap_only)?;
+        crate::compsys_export::generate_all(&project_specs, existing, output, gap_only)?;
 
-    if !force {
-        let generated_set: std::collections::HashSet<String> =
-            report.generated.iter().cloned().collect();
-        report.removed = crate::compsys_export::remove_stale_project_auto(&output, &generated_set)?;
+    if !force && (in_project || prune_outside_project) {
+        let generated_set: HashSet<String> = report.generated.iter().cloned().collect();
+        report.removed = crate::compsys_export::remove_stale_project_auto(output, &generated_set)?;
     }
 
     println!(
@@ -75,3 +124,12 @@ pub(super) async fn scan_project(
 
     Ok(())
 }
+
+/// Modification times of the project's spec inputs; a missing path is `None`,
+/// so creating or deleting one also counts as a change.
+fn fingerprint(cwd: &Path) -> Vec<Option<SystemTime>> {
+    crate::spec_autogen::spec_inputs(cwd)
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
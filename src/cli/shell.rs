@@ -131,10 +131,11 @@ fn print_dev_init_code(
         r#"# synapse dev mode
 export SYNAPSE_BIN="{exe}"
 fpath=("$HOME/.synapse/completions" $fpath)
-source "{plugin}"
+{settings}source "{plugin}"
 echo "synapse dev: ready" >&2
 "#,
         exe = exe.display(),
+        settings = plugin_settings(),
         plugin = plugin_path.display(),
     );
     Ok(())
@@ -144,23 +145,70 @@ echo "synapse dev: ready" >&2
 fn print_normal_init_code(exe: &std::path::Path) -> anyhow::Result<()> {
     let plugin_path = find_plugin_path(exe, None)?;
 
-    // Update notification (cache read only, no network)
-    if let Some(version) = super::update::cached_update_available() {
-        eprintln!("synapse: update available ({version}). Run: synapse update");
+    // Update notification (cache read only, no network), once per version
+    let config = crate::config::Config::load();
+    if config.check_updates {
+        if let Some(version) = super::update::take_update_notice() {
+            eprintln!("synapse: update available ({version}). Run: synapse self-update");
+        }
     }
 
+    // Background spec sync (throttled to once a day by the command itself)
+    let spec_sync = if config.spec.remote_sources.is_empty() {
+        ""
+    } else {
+        "(command \"$SYNAPSE_BIN\" spec sync --background &>/dev/null &)\n"
+    };
+
     print!(
         r#"export SYNAPSE_BIN="{exe}"
 fpath=("$HOME/.synapse/completions" $fpath)
-source "{plugin}"
-(command "$SYNAPSE_BIN" update --check &>/dev/null &)
-"#,
+{settings}source "{plugin}"
+{update_check}(command "$SYNAPSE_BIN" completions sync --background &>/dev/null &)
+{spec_sync}"#,
         exe = exe.display(),
+        settings = plugin_settings(),
         plugin = plugin_path.display(),
+        update_check = if config.check_updates {
+            "(command \"$SYNAPSE_BIN\" update --check &>/dev/null &)\n"
+        } else {
+            ""
+        },
     );
     Ok(())
 }
 
+/// Config-derived plugin variables, set before the plugin is sourced so the
+/// plugin never has to parse config itself.
+fn plugin_settings() -> String {
+    let config = crate::config::Config::load();
+    let mut settings = String::new();
+    if config.memory.enabled {
+        settings.push_str("typeset -gi _SYNAPSE_MEMORY=1\n");
+    }
+    if !config.completions.scan_on_cd || !config.spec.enabled || !config.spec.auto_generate {
+        settings.push_str("typeset -gi _SYNAPSE_SCAN_ON_CD=0\n");
+    }
+    if config.spec.validate_on_enter {
+        settings.push_str("typeset -gi _SYNAPSE_VALIDATE=1\n");
+    }
+    if !config.memory.durations {
+        settings.push_str("typeset -gi _SYNAPSE_DURATIONS=0\n");
+    }
+    if config.llm.few_shot_examples == 0 {
+        settings.push_str("typeset -gi _SYNAPSE_NL_EXAMPLES=0\n");
+    }
+    let confirm_risk = config
+        .security
+        .confirm_threshold()
+        .map_or(0, crate::llm::Severity::level);
+    settings.push_str(&format!(
+        "typeset -gi _SYNAPSE_CONFIRM_RISK={confirm_risk}\n"
+    ));
+    settings.push_str(&super::snippet::zsh_assignment());
+    settings
+}
+
 /// Idempotently add the init line to a shell RC file.
 /// If `compinit` is found in the file, the init line is inserted before it
 /// (synapse must add to fpath before compinit scans). Otherwise, appends.
@@ -208,7 +256,7 @@ pub fn setup_shell_rc(rc_file: &str) -> anyhow::Result<()> {
 
 /// Find the byte offset of the start of the first non-commented line
 /// containing `compinit`. Returns `None` if no such line exists.
-fn find_compinit_line_start(contents: &str) -> Option<usize> {
+pub(super) fn find_compinit_line_start(contents: &str) -> Option<usize> {
     let mut offset = 0;
     for line in contents.lines() {
         let trimmed = line.trim();
@@ -0,0 +1,59 @@
+use anyhow::Context;
+
+use crate::snippets;
+
+pub(super) fn add(trigger: String, expansion: String) -> anyhow::Result<()> {
+    if !snippets::is_valid_trigger(&trigger) {
+        anyhow::bail!("invalid trigger '{trigger}' (use a single word without quotes)");
+    }
+    if expansion.trim().is_empty() {
+        anyhow::bail!("expansion must not be empty");
+    }
+    let path = snippets::snippets_path().context("cannot determine home directory")?;
+    snippets::add(&path, &trigger, &expansion)?;
+    Ok(())
+}
+
+pub(super) fn remove(trigger: String) -> anyhow::Result<()> {
+    let path = snippets::snippets_path().context("cannot determine home directory")?;
+    if !snippets::remove(&path, &trigger)? {
+        anyhow::bail!("no snippet '{trigger}'");
+    }
+    Ok(())
+}
+
+pub(super) fn list(zsh: bool) -> anyhow::Result<()> {
+    if zsh {
+        print!("{}", zsh_assignment());
+        return Ok(());
+    }
+    for (trigger, expansion) in snippets::load_all() {
+        println!("{trigger}\t{expansion}");
+    }
+    Ok(())
+}
+
+/// The plugin's `_SYNAPSE_SNIPPETS` associative array, plus the triggers that
+/// come from team snippets (`_SYNAPSE_TEAM_TRIGGERS`), as zsh source.
+pub(super) fn zsh_assignment() -> String {
+    let quote = crate::compsys_export::shell_quote;
+    let entries: Vec<String> = snippets::load_all()
+        .iter()
+        .map(|(trigger, expansion)| format!("{} {}", quote(trigger), quote(expansion)))
+        .collect();
+    let personal = snippets::snippets_path()
+        .map(|p| snippets::load(&p))
+        .unwrap_or_default();
+    let team: Vec<String> = snippets::team_snippets_path()
+        .map(|p| snippets::load(&p))
+        .unwrap_or_default()
+        .into_keys()
+        .filter(|trigger| !personal.contains_key(trigger))
+        .map(|trigger| quote(&trigger))
+        .collect();
+    format!(
+        "typeset -gA _SYNAPSE_SNIPPETS=({})\ntypeset -ga _SYNAPSE_TEAM_TRIGGERS=({})\n",
+        entries.join(" "),
+        team.join(" ")
+    )
+}
@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use crate::config::Config;
+use crate::spec_bundle;
+use crate::spec_store::SpecStore;
+use crate::spec_sync;
+
+use super::scan::resolve_completions_dir;
+
+pub(super) async fn export(
+    command: String,
+    output: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let config = Config::load();
+    let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
+    let completions_dir = resolve_completions_dir(&config, None);
+    let spec_store = SpecStore::with_completions_dir(config.spec.clone(), completions_dir);
+
+    let Some(spec) = spec_store.lookup_with_system_fallback(&command, &cwd).await else {
+        anyhow::bail!("no spec found for '{command}' (try `synapse add {command}` first)");
+    };
+    let content = toml::to_string(&spec_bundle::export(&spec))?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("Exported {command} to {}", path.display());
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+pub(super) async fn import(
+    source: String,
+    trust_generators: bool,
+    output_dir: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let config = Config::load();
+    if source.starts_with("http://") {
+        anyhow::bail!("bundle URLs must use https");
+    }
+    let is_url = source.starts_with("https://");
+
+    let content = if is_url {
+        if config.is_offline() {
+            anyhow::bail!("offline mode is enabled; download the bundle and import the file");
+        }
+        reqwest::get(&source)
+            .await?
+            .error_for_status()
+            .context("failed to download bundle")?
+            .text()
+            .await?
+    } else {
+        std::fs::read_to_string(&source).with_context(|| format!("failed to read {source}"))?
+    };
+
+    let bundle = spec_bundle::parse(&content)?;
+    let completions_dir = resolve_completions_dir(&config, output_dir);
+    if let Some(alias) = spec_bundle::taken_alias(&bundle.spec, &completions_dir) {
+        anyhow::bail!("alias '{alias}' already has a completion; not importing");
+    }
+    let generators = bundle.bundle.generators.clone();
+    let specs_dir = spec_bundle::specs_dir().context("cannot determine home directory")?;
+    let spec = spec_bundle::install(bundle, &source, trust_generators, &specs_dir)?;
+
+    let path = crate::compsys_export::write_completion_file(&spec, &completions_dir)?;
+
+    println!("Imported {} from {source}", spec.name);
+    println!("  Wrote {}", path.display());
+    if !generators.is_empty() {
+        if trust_generators {
+            println!("  Trusted {} generator(s):", generators.len());
+        } else {
+            println!(
+                "  Dropped {} generator(s); re-import with --trust-generators to keep them:",
+                generators.len()
+            );
+        }
+        for command in generators {
+            println!("    {command}");
+        }
+    }
+    Ok(())
+}
+
+pub(super) async fn sync(background: bool, output_dir: Option<PathBuf>) -> anyhow::Result<()> {
+    let config = Config::load();
+    let sources = &config.spec.remote_sources;
+    if background && (sources.is_empty() || config.is_offline() || !spec_sync::sync_due()) {
+        return Ok(());
+    }
+    if sources.is_empty() {
+        println!("No remote spec sources configured (see [[spec.remote_sources]])");
+        return Ok(());
+    }
+    if config.is_offline() {
+        anyhow::bail!("offline mode is enabled (unset SYNAPSE_OFFLINE or set offline = false)");
+    }
+
+    let install_dir = spec_sync::remote_specs_dir().context("cannot determine home directory")?;
+    let completions_dir = resolve_completions_dir(&config, output_dir);
+    let report = spec_sync::sync(sources, &install_dir, &completions_dir).await;
+
+    for spec in &report.installed {
+        crate::compsys_export::write_completion_file(spec, &completions_dir)?;
+    }
+    for name in &report.removed {
+        let _ = std::fs::remove_file(completions_dir.join(format!("_{name}")));
+    }
+    if background {
+        return Ok(());
+    }
+
+    println!(
+        "Synced {} specs into {}",
+        report.installed.len(),
+        install_dir.display()
+    );
+    for spec in &report.installed {
+        println!("  _{}", spec.name);
+    }
+    if report.snippets > 0 {
+        println!("Synced {} team snippets", report.snippets);
+    }
+    if !report.removed.is_empty() {
+        println!("Removed {} specs no longer offered", report.removed.len());
+        for name in &report.removed {
+            println!("  _{name}");
+        }
+    }
+    for error in &report.errors {
+        eprintln!("error: {error}");
+    }
+    if !report.errors.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
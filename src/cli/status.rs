@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::spec::SpecSource;
+
+use super::scan::resolve_completions_dir;
+
+#[derive(Serialize)]
+struct Status {
+    version: &'static str,
+    config_path: PathBuf,
+    /// False when no config file exists and defaults are in effect.
+    config_found: bool,
+    updates: UpdateStatus,
+    completions: CompletionsStatus,
+    llm: LlmStatus,
+    /// Size in bytes of each entry under `~/.synapse/`.
+    cache_bytes: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+struct UpdateStatus {
+    enabled: bool,
+    /// Newer release found by the last check.
+    available: Option<String>,
+    /// Unix seconds of the last check.
+    last_checked: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct CompletionsStatus {
+    dir: PathBuf,
+    discovered: usize,
+    project: usize,
+    imported: usize,
+}
+
+#[derive(Serialize)]
+struct LlmStatus {
+    enabled: bool,
+    endpoint: String,
+    model: String,
+    /// Whether translation can run at all (key present, not blocked offline).
+    available: bool,
+    /// Result of probing a local endpoint; remote endpoints are not probed.
+    reachable: Option<bool>,
+}
+
+/// Print the installed version, config file, update-check result, generated
+/// completion counts, LLM availability, and cache sizes. `--json` prints the
+/// same report as one JSON object for scripts.
+pub(super) async fn status(json: bool) -> anyhow::Result<()> {
+    let config = Config::load();
+    let config_path = Config::path();
+    let completions_dir = resolve_completions_dir(&config, None);
+
+    let mut completions = CompletionsStatus {
+        dir: completions_dir.clone(),
+        discovered: 0,
+        project: 0,
+        imported: 0,
+    };
+    for file in crate::compsys_export::list_generated(&completions_dir) {
+        match file.source {
+            SpecSource::Discovered => completions.discovered += 1,
+            SpecSource::ProjectAuto => completions.project += 1,
+            SpecSource::Imported => completions.imported += 1,
+        }
+    }
+
+    let status = Status {
+        version: env!("CARGO_PKG_VERSION"),
+        config_found: config_path.exists(),
+        config_path,
+        updates: UpdateStatus {
+            enabled: config.check_updates,
+            available: super::update::cached_update_available(),
+            last_checked: super::update::last_checked(),
+        },
+        completions,
+        llm: llm_status(&config).await,
+        cache_bytes: dirs::home_dir()
+            .map(|home| cache_sizes(&home.join(".synapse")))
+            .unwrap_or_default(),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+    } else {
+        print_report(&status);
+    }
+    Ok(())
+}
+
+async fn llm_status(config: &Config) -> LlmStatus {
+    let endpoint = config
+        .llm
+        .base_url
+        .clone()
+        .unwrap_or_else(|| "https://api.openai.com".to_string());
+    let local = crate::llm::is_local_base_url(&endpoint);
+    let client = if config.llm_blocked_offline() {
+        None
+    } else {
+        crate::llm::LlmClient::from_config(&config.llm)
+    };
+    let available = client.is_some();
+    let (model, reachable) = match client {
+        Some(mut client) if local => match client.auto_detect_model().await {
+            Some(model) => (model, Some(true)),
+            None => (config.llm.model.clone(), Some(false)),
+        },
+        _ => (config.llm.model.clone(), None),
+    };
+    LlmStatus {
+        enabled: config.llm.enabled,
+        endpoint,
+        model,
+        available,
+        reachable,
+    }
+}
+
+fn print_report(status: &Status) {
+    println!("synapse {}", status.version);
+    if status.config_found {
+        println!("config:      {}", status.config_path.display());
+    } else {
+        println!(
+            "config:      defaults ({} not found)",
+            status.config_path.display()
+        );
+    }
+
+    let updates = &status.updates;
+    let update_line = if !updates.enabled {
+        "checks disabled (set check_updates = true)".to_string()
+    } else if let Some(version) = &updates.available {
+        format!("{version} available (run `synapse self-update`)")
+    } else if updates.last_checked.is_some() {
+        "up to date".to_string()
+    } else {
+        "not checked yet".to_string()
+    };
+    println!("updates:     {update_line}");
+
+    let completions = &status.completions;
+    println!(
+        "completions: {} discovered, {} project, {} imported in {}",
+        completions.discovered,
+        completions.project,
+        completions.imported,
+        completions.dir.display()
+    );
+
+    let llm = &status.llm;
+    let llm_line = if !llm.enabled {
+        "disabled".to_string()
+    } else if !llm.available {
+        format!("unavailable ({}; API key unset or offline)", llm.endpoint)
+    } else {
+        let reach = match llm.reachable {
+            Some(true) => "reachable",
+            Some(false) => "not responding",
+            None => "not probed",
+        };
+        format!("{} at {} ({reach})", llm.model, llm.endpoint)
+    };
+    println!("llm:         {llm_line}");
+
+    if !status.cache_bytes.is_empty() {
+        println!("cache:");
+        for (name, bytes) in &status.cache_bytes {
+            println!("  {name:<24} {}", human_size(*bytes));
+        }
+    }
+}
+
+/// Recursive size of each top-level entry in `dir`.
+fn cache_sizes(dir: &Path) -> BTreeMap<String, u64> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return BTreeMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            Some((name, disk_size(&entry.path())))
+        })
+        .collect()
+}
+
+fn disk_size(path: &Path) -> u64 {
+    let Ok(meta) = path.symlink_metadata() else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| disk_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
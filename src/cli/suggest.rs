@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::spec_store::SpecStore;
+
+/// Print spec-based completions for the end of `buffer`, one `text\tdesc`
+/// line each, or as a JSON array.
+pub(super) async fn suggest(
+    buffer: String,
+    cwd: Option<PathBuf>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let config = Config::load();
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()?,
+    };
+
+    let spec_store = SpecStore::new(config.spec.clone());
+    let spec = match buffer.split_whitespace().next() {
+        Some(program) => spec_store.lookup_with_system_fallback(program, &cwd).await,
+        None => None,
+    };
+    let remembered = crate::flag_values::flag_values_path()
+        .map(|path| crate::flag_values::load(&path))
+        .unwrap_or_default();
+    let candidates = spec
+        .map(|spec| crate::suggest::suggest(&spec, &buffer, &remembered))
+        .unwrap_or_default();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&candidates)?);
+    } else {
+        for candidate in candidates {
+            println!(
+                "{}\t{}",
+                candidate.text,
+                candidate.description.as_deref().unwrap_or("")
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+use crate::providers::terraform::resource_addresses;
+
+pub(super) fn list_addresses(cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let cwd = cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
+    for address in resource_addresses(&cwd) {
+        println!("{address}");
+    }
+    Ok(())
+}
@@ -1,32 +1,54 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
-
-use regex::Regex;
+use std::path::{Path, PathBuf};
 
 use crate::config::Config;
-use crate::llm::NlTranslationContext;
+use crate::llm::{NlTranslationContext, RelevantSpec};
+use crate::security::PolicyEngine;
 use crate::spec_store::SpecStore;
 
-/// Maximum entries in the directory listing included in NL context.
-const MAX_CWD_ENTRIES: usize = 50;
-/// Maximum flags per tool to include in NL context.
-const MAX_FLAGS_PER_TOOL: usize = 20;
+/// Maximum directory entries considered for NL context (packed to the token budget).
+const MAX_CWD_ENTRIES: usize = 200;
+/// Maximum flags per tool considered for NL context (packed to the token budget).
+const MAX_FLAGS_PER_TOOL: usize = 100;
+/// Maximum subcommands per tool considered for NL context (packed to the token budget).
+const MAX_SUBCOMMANDS_PER_TOOL: usize = 100;
+/// Maximum remembered entities to include in NL context.
+const MAX_RECENT_ENTITIES: usize = 15;
+/// Timeout in ms for `tmux capture-pane`.
+const TMUX_CAPTURE_TIMEOUT_MS: u64 = 500;
+
+/// What `translate` does with the suggestions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Output {
+    /// TSV for the plugin (stdout is not a terminal).
+    Tsv,
+    /// Pick from a numbered list and print the chosen command.
+    Print,
+    /// Pick, then copy the command to the clipboard.
+    Copy,
+    /// Pick, confirm, then run the command.
+    Execute,
+}
 
 pub(super) async fn translate(
     query: String,
     cwd: PathBuf,
     recent_commands: Vec<String>,
     env_hints_raw: Vec<String>,
+    output: Output,
 ) -> anyhow::Result<()> {
     let config = Config::load();
 
     if query.len() < crate::config::NL_MIN_QUERY_LENGTH {
-        print_error(&format!(
-            "Natural language query too short (minimum {} characters)",
-            crate::config::NL_MIN_QUERY_LENGTH
-        ));
-        return Ok(());
+        return fail(
+            output,
+            ErrorCode::InvalidRequest,
+            &format!(
+                "Natural language query too short (minimum {} characters)",
+                crate::config::NL_MIN_QUERY_LENGTH
+            ),
+        );
     }
 
     let env_hints: HashMap<String, String> = env_hints_raw
@@ -37,11 +59,22 @@ pub(super) async fn translate(
         })
         .collect();
 
+    if config.llm_blocked_offline() {
+        return fail(
+            output,
+            ErrorCode::Offline,
+            "offline: LLM endpoint is not local",
+        );
+    }
+
     let mut llm_client = match crate::llm::LlmClient::from_config(&config.llm) {
         Some(client) => client,
         None => {
-            print_error("LLM client not configured (set llm.enabled and API key)");
-            return Ok(());
+            return fail(
+                output,
+                ErrorCode::LlmUnavailable,
+                "LLM client not configured (set llm.enabled and API key)",
+            );
         }
     };
     llm_client.auto_detect_model().await;
@@ -62,41 +95,319 @@ pub(super) async fn translate(
     {
         Ok(result) => result,
         Err(e) => {
-            print_error(&format!("Natural language translation failed: {e}"));
-            return Ok(());
+            return fail(
+                output,
+                ErrorCode::from_llm_error(&e),
+                &format!("Natural language translation failed: {e}"),
+            );
         }
     };
 
-    let blocklist = CompiledBlocklist::new(&config.security.command_blocklist);
+    let is_plan = result.is_plan;
+    let item_count = result.items.len();
+    let mut valid_items = apply_policy(result.items, "llm", &cwd, &config);
 
-    let valid_items: Vec<_> = result
-        .items
+    if valid_items.is_empty() {
+        return fail(
+            output,
+            ErrorCode::Blocked,
+            "All NL translations were empty or blocked by security policy",
+        );
+    }
+
+    // Dropping a step would leave a plan that no longer does what was asked.
+    if is_plan && valid_items.len() < item_count {
+        return fail(
+            output,
+            ErrorCode::Blocked,
+            "A step of the NL plan was blocked by security policy",
+        );
+    }
+
+    localize_risks(&mut valid_items, context.language);
+
+    let durations = crate::durations::durations_path()
+        .map(|path| crate::durations::load(&path))
+        .unwrap_or_default();
+    if output != Output::Tsv {
+        let chosen = if is_plan {
+            show_plan(&valid_items)
+        } else {
+            pick(&valid_items, &cwd, &durations)?
+        };
+        return deliver(output, chosen, &cwd, &config);
+    }
+
+    let out = if is_plan {
+        format_plan(&valid_items)
+    } else {
+        format_suggestion_list(&valid_items, "llm", &cwd, &durations)
+    };
+    println!("{out}");
+
+    Ok(())
+}
+
+/// Drop empty suggestions and those the security policy blocks for `source`,
+/// logging blocked ones to the policy audit log.
+pub(super) fn apply_policy(
+    items: Vec<crate::llm::NlTranslationItem>,
+    source: &str,
+    cwd: &Path,
+    config: &Config,
+) -> Vec<crate::llm::NlTranslationItem> {
+    let policy = PolicyEngine::new(&config.security);
+    let audit_path = config
+        .security
+        .audit_log
+        .then(crate::security::audit_log_path)
+        .flatten();
+
+    items
         .into_iter()
         .filter(|item| {
-            let first_token = item.command.split_whitespace().next().unwrap_or("");
-            !first_token.is_empty() && !blocklist.is_blocked(&item.command)
+            if item.command.split_whitespace().next().is_none() {
+                return false;
+            }
+            let decision = policy.evaluate(&item.command, source, cwd);
+            if let (Some(rule), Some(path)) = (&decision.rule, &audit_path) {
+                if !decision.allowed {
+                    let _ = crate::security::record_blocked(path, &item.command, source, cwd, rule);
+                }
+            }
+            decision.allowed
         })
-        .collect();
+        .collect()
+}
 
-    if valid_items.is_empty() {
-        print_error("All NL translations were empty or blocked by security policy");
+/// Replace each risk rationale with its text in `language`.
+pub(super) fn localize_risks(
+    items: &mut [crate::llm::NlTranslationItem],
+    language: crate::i18n::Language,
+) {
+    for risk in items.iter_mut().filter_map(|item| item.risk.as_mut()) {
+        risk.rationale = crate::i18n::localize(language, risk.reason).to_string();
+    }
+}
+
+/// The risk rationale, glob preview and last duration of a suggestion.
+fn describe_item(
+    item: &crate::llm::NlTranslationItem,
+    cwd: &Path,
+    durations: &[crate::durations::CommandDuration],
+) -> String {
+    let mut notes: Vec<String> = Vec::new();
+    if let Some(risk) = &item.risk {
+        notes.push(risk.rationale.clone());
+    }
+    notes.extend(crate::preview::preview(&item.command, cwd));
+    notes.extend(crate::durations::note(durations, &item.command));
+    notes.join("; ")
+}
+
+fn show_plan(steps: &[crate::llm::NlTranslationItem]) -> &[crate::llm::NlTranslationItem] {
+    eprintln!("Plan:");
+    for (i, step) in steps.iter().enumerate() {
+        eprintln!("{:>3}. {}", i + 1, step.command);
+    }
+    steps
+}
+
+/// List the alternatives on stderr and read a choice from stdin.
+fn pick<'a>(
+    items: &'a [crate::llm::NlTranslationItem],
+    cwd: &Path,
+    durations: &[crate::durations::CommandDuration],
+) -> anyhow::Result<&'a [crate::llm::NlTranslationItem]> {
+    for (i, item) in items.iter().enumerate() {
+        eprintln!("{:>3}. {}", i + 1, item.command);
+        let desc = describe_item(item, cwd, durations);
+        match &item.risk {
+            Some(risk) => eprintln!("     [{}] {desc}", risk.severity.name()),
+            None if !desc.is_empty() => eprintln!("     {desc}"),
+            None => {}
+        }
+    }
+    if items.len() == 1 {
+        return Ok(items);
+    }
+    let answer = prompt(&format!("Pick [1-{}] (Enter for 1): ", items.len()))?;
+    let index = if answer.is_empty() {
+        1
+    } else {
+        answer
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=items.len()).contains(n))
+            .ok_or_else(|| anyhow::anyhow!("no such suggestion: {answer}"))?
+    };
+    Ok(&items[index - 1..index])
+}
+
+/// Print, copy or run the chosen command (or every step of a plan).
+fn deliver(
+    output: Output,
+    chosen: &[crate::llm::NlTranslationItem],
+    cwd: &Path,
+    config: &Config,
+) -> anyhow::Result<()> {
+    match output {
+        Output::Tsv | Output::Print => {
+            for item in chosen {
+                println!("{}", item.command);
+            }
+        }
+        Output::Copy => {
+            let text: Vec<&str> = chosen.iter().map(|item| item.command.as_str()).collect();
+            copy_to_clipboard(&text.join("\n"))?;
+            eprintln!("Copied to clipboard");
+        }
+        Output::Execute => execute(chosen, cwd, config)?,
+    }
+    Ok(())
+}
+
+/// Clipboard tools tried in order: macOS, Wayland, then X11.
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["pbcopy"],
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+fn copy_to_clipboard(text: &str) -> anyhow::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    for argv in CLIPBOARD_COMMANDS {
+        let Ok(mut child) = Command::new(argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    anyhow::bail!("no clipboard tool found (pbcopy, wl-copy, xclip or xsel)")
+}
+
+/// Run the commands in `$SHELL` after confirmation, stopping at the first
+/// failure and exiting with its status. At or above `security.confirm_risk`
+/// the user must type `yes` rather than `y`.
+fn execute(
+    steps: &[crate::llm::NlTranslationItem],
+    cwd: &Path,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let threshold = config.security.confirm_threshold();
+    let risky = steps
+        .iter()
+        .filter_map(|step| step.risk.as_ref())
+        .find(|risk| threshold.is_some_and(|t| risk.severity >= t));
+    let what = if steps.len() == 1 {
+        "it"
+    } else {
+        "these steps"
+    };
+    let confirmed = match risky {
+        Some(risk) => {
+            let question = format!(
+                "This {} risk command {}. Type 'yes' to run {what}: ",
+                risk.severity.name(),
+                risk.rationale
+            );
+            prompt(&question)? == "yes"
+        }
+        None => matches!(
+            prompt(&format!("Run {what}? [y/N] "))?.as_str(),
+            "y" | "Y" | "yes"
+        ),
+    };
+    if !confirmed {
+        eprintln!("Not run");
         return Ok(());
     }
 
-    // Output TSV: list\t<count>\t<text>\t<source>\t<desc>\t<kind>\t...
-    let count = valid_items.len();
-    let mut out = format!("list\t{count}");
-    for item in &valid_items {
-        let desc = item.warning.as_deref().unwrap_or("");
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    for step in steps {
+        if steps.len() > 1 {
+            eprintln!("$ {}", step.command);
+        }
+        let status = std::process::Command::new(&shell)
+            .arg("-c")
+            .arg(&step.command)
+            .current_dir(cwd)
+            .status()?;
+        if !status.success() {
+            std::process::exit(status.code().unwrap_or(1));
+        }
+    }
+    Ok(())
+}
+
+/// Ask on stderr and read one trimmed line from stdin (empty at EOF).
+pub(super) fn prompt(question: &str) -> std::io::Result<String> {
+    eprint!("{question}");
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Format items as the TSV the plugin parses:
+/// list\t<count>\t<text>\t<source>\t<desc>\t<kind>\t<risk>\t...
+///
+/// `<risk>` is the severity name (empty when the command looks harmless) and
+/// `<desc>` carries the risk rationale, followed by how many files any globs
+/// of a file-mutating command match in `cwd` and how long the command took
+/// when it last ran.
+pub(super) fn format_suggestion_list(
+    items: &[crate::llm::NlTranslationItem],
+    source: &str,
+    cwd: &Path,
+    durations: &[crate::durations::CommandDuration],
+) -> String {
+    let mut out = format!("list\t{}", items.len());
+    for item in items {
+        let risk = item.risk.as_ref().map_or("", |r| r.severity.name());
+        let desc = describe_item(item, cwd, durations);
         out.push('\t');
         out.push_str(&sanitize_tsv(&item.command));
-        out.push_str("\tllm\t");
-        out.push_str(&sanitize_tsv(desc));
-        out.push_str("\tcommand");
+        out.push('\t');
+        out.push_str(source);
+        out.push('\t');
+        out.push_str(&sanitize_tsv(&desc));
+        out.push_str("\tcommand\t");
+        out.push_str(risk);
     }
-    println!("{out}");
+    out
+}
 
-    Ok(())
+/// Format plan steps as the TSV the plugin parses:
+/// plan\t<count>\t<step>\t<risk>\t<rationale>\t...
+///
+/// `<risk>` and `<rationale>` are empty when the step looks harmless.
+fn format_plan(steps: &[crate::llm::NlTranslationItem]) -> String {
+    let mut out = format!("plan\t{}", steps.len());
+    for step in steps {
+        let (risk, rationale) = step
+            .risk
+            .as_ref()
+            .map_or(("", ""), |r| (r.severity.name(), r.rationale.as_str()));
+        out.push('\t');
+        out.push_str(&sanitize_tsv(&step.command));
+        out.push('\t');
+        out.push_str(risk);
+        out.push('\t');
+        out.push_str(&sanitize_tsv(rationale));
+    }
+    out
 }
 
 async fn prepare_nl_context(
@@ -110,11 +421,12 @@ async fn prepare_nl_context(
     let cwd_str = cwd.to_string_lossy().to_string();
     let scan_depth = config.spec.scan_depth;
 
-    let (project_root, available_tools, git_branch, cwd_entries) = tokio::join!(
+    let (project_root, available_tools, git_branch, cwd_entries, terminal_output) = tokio::join!(
         async { crate::project::find_project_root(cwd, scan_depth) },
         async { extract_available_tools(env_hints) },
         async { crate::project::read_git_branch_for_path(cwd) },
         async { read_cwd_entries(cwd).await },
+        capture_tmux_pane(env_hints, config.llm.tmux_context_lines),
     );
 
     let project_type = match project_root.as_ref() {
@@ -123,8 +435,19 @@ async fn prepare_nl_context(
     };
 
     let spec_store = SpecStore::new(config.spec.clone());
-    let project_commands = extract_project_commands(&spec_store, cwd).await;
+    let mut project_commands = extract_project_commands(&spec_store, cwd).await;
+    let env_scripts = python_env_scripts(env_hints);
+    if !env_scripts.is_empty() {
+        project_commands.insert("python env".to_string(), env_scripts);
+    }
     let relevant_specs = extract_relevant_specs(&spec_store, query, cwd).await;
+    let recent_entities = if config.memory.enabled {
+        read_recent_entities()
+    } else {
+        Vec::new()
+    };
+
+    let few_shot_examples = read_few_shot_examples(query, config.llm.few_shot_examples);
 
     NlTranslationContext {
         query: query.to_string(),
@@ -137,9 +460,68 @@ async fn prepare_nl_context(
         project_commands,
         cwd_entries,
         relevant_specs,
+        recent_entities,
+        terminal_output,
+        few_shot_examples,
+        context_tokens: config.llm.context_tokens,
+        language: crate::i18n::Language::resolve(config.general.language.as_deref(), |key| {
+            env_hints.get(key).cloned()
+        }),
     }
 }
 
+/// Capture the last `lines` lines of the current tmux pane, scrubbed of
+/// secrets. Only runs when enabled and `TMUX` was passed as an env hint.
+async fn capture_tmux_pane(env_hints: &HashMap<String, String>, lines: usize) -> Option<String> {
+    if lines == 0 || !env_hints.contains_key("TMUX") {
+        return None;
+    }
+
+    let mut cmd = tokio::process::Command::new("tmux");
+    cmd.args(["capture-pane", "-p", "-J", "-S", &format!("-{lines}")]);
+    if let Some(pane) = env_hints.get("TMUX_PANE") {
+        cmd.args(["-t", pane]);
+    }
+    cmd.stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+
+    let timeout = std::time::Duration::from_millis(TMUX_CAPTURE_TIMEOUT_MS);
+    let output = match tokio::time::timeout(timeout, cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        _ => return None,
+    };
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let captured: Vec<&str> = text.trim_end().lines().collect();
+    let start = captured.len().saturating_sub(lines);
+    let tail = captured[start..].join("\n");
+    (!tail.trim().is_empty()).then(|| crate::llm::scrub_secrets(&tail))
+}
+
+fn read_recent_entities() -> Vec<String> {
+    let Some(path) = crate::memory::memory_path() else {
+        return Vec::new();
+    };
+    crate::memory::recent(&path, None, MAX_RECENT_ENTITIES)
+        .into_iter()
+        .map(|entity| format!("{} {}", entity.kind.name(), entity.value))
+        .collect()
+}
+
+fn read_few_shot_examples(query: &str, k: usize) -> Vec<(String, String)> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let Some(path) = crate::nl_examples::examples_path() else {
+        return Vec::new();
+    };
+    let examples = crate::nl_examples::load(&path);
+    crate::nl_examples::most_similar(&examples, query, k)
+        .into_iter()
+        .map(|e| (e.query, e.command))
+        .collect()
+}
+
 async fn read_cwd_entries(cwd: &std::path::Path) -> Vec<String> {
     let cwd = cwd.to_path_buf();
     tokio::task::spawn_blocking(move || {
@@ -178,6 +560,18 @@ async fn extract_project_commands(
             spec.subcommands.iter().map(|s| s.name.clone()).collect(),
         );
     }
+    let pins = crate::pins::load(cwd);
+    if !pins.is_empty() {
+        commands.insert("pinned".to_string(), pins);
+    }
+    let scripts = crate::spec_autogen::project_scripts(cwd);
+    if !scripts.is_empty() {
+        commands.insert("scripts".to_string(), scripts);
+    }
+    let node_bins = crate::spec_autogen::node_bins(cwd);
+    if !node_bins.is_empty() {
+        commands.insert("npx".to_string(), node_bins);
+    }
     commands
 }
 
@@ -185,22 +579,34 @@ async fn extract_relevant_specs(
     spec_store: &SpecStore,
     query: &str,
     cwd: &std::path::Path,
-) -> HashMap<String, Vec<String>> {
-    let query_tokens: Vec<&str> = query.split_whitespace().collect();
+) -> HashMap<String, RelevantSpec> {
+    // Trim punctuation so "git?" or "`docker`" still name a tool.
+    let query_tokens: Vec<&str> = query
+        .split_whitespace()
+        .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '_'))
+        .collect();
     let all_names = spec_store.all_command_names(cwd).await;
     let mut result = HashMap::new();
 
     for name in &all_names {
         if query_tokens.iter().any(|t| t.eq_ignore_ascii_case(name)) {
-            if let Some(spec) = spec_store.lookup(name, cwd).await {
-                let flags: Vec<String> = spec
-                    .options
-                    .iter()
-                    .take(MAX_FLAGS_PER_TOOL)
-                    .filter_map(|opt| opt.long.as_ref().or(opt.short.as_ref()).cloned())
-                    .collect();
-                if !flags.is_empty() {
-                    result.insert(name.clone(), flags);
+            if let Some(spec) = spec_store.lookup_with_system_fallback(name, cwd).await {
+                let relevant = RelevantSpec {
+                    subcommands: spec
+                        .subcommands
+                        .iter()
+                        .take(MAX_SUBCOMMANDS_PER_TOOL)
+                        .map(|sub| sub.name.clone())
+                        .collect(),
+                    flags: spec
+                        .options
+                        .iter()
+                        .take(MAX_FLAGS_PER_TOOL)
+                        .filter_map(|opt| opt.long.as_ref().or(opt.short.as_ref()).cloned())
+                        .collect(),
+                };
+                if !relevant.subcommands.is_empty() || !relevant.flags.is_empty() {
+                    result.insert(name.clone(), relevant);
                 }
             }
         }
@@ -328,47 +734,22 @@ fn extract_available_tools(env_hints: &HashMap<String, String>) -> Vec<String> {
     found
 }
 
-// --- Blocklist ---
-
-struct CompiledBlocklist {
-    patterns: Vec<CompiledBlockPattern>,
-}
-
-enum CompiledBlockPattern {
-    Substring(String),
-    Regex(Regex),
-}
-
-impl CompiledBlocklist {
-    fn new(raw_patterns: &[String]) -> Self {
-        let patterns = raw_patterns
-            .iter()
-            .filter_map(|p| {
-                let trimmed = p.trim();
-                if trimmed.is_empty() {
-                    return None;
-                }
-                if !trimmed.contains('*') && !trimmed.contains('?') {
-                    return Some(CompiledBlockPattern::Substring(trimmed.to_string()));
-                }
-                let regex_pattern = regex::escape(trimmed)
-                    .replace(r"\*", ".*")
-                    .replace(r"\?", ".");
-                match Regex::new(&regex_pattern) {
-                    Ok(re) => Some(CompiledBlockPattern::Regex(re)),
-                    Err(_) => Some(CompiledBlockPattern::Substring(trimmed.to_string())),
-                }
-            })
-            .collect();
-        Self { patterns }
-    }
+/// Console scripts installed in the active virtualenv (or conda env), such
+/// as `pytest` or `black`, which the user can run directly.
+fn python_env_scripts(env_hints: &HashMap<String, String>) -> Vec<String> {
+    /// Interpreter, installer and activation files every environment has.
+    const STOCK_PREFIXES: &[&str] = &["python", "pip", "activate", "deactivate", "easy_install"];
 
-    fn is_blocked(&self, command: &str) -> bool {
-        self.patterns.iter().any(|p| match p {
-            CompiledBlockPattern::Substring(s) => command.contains(s.as_str()),
-            CompiledBlockPattern::Regex(re) => re.is_match(command),
-        })
-    }
+    let Some(prefix) = ["VIRTUAL_ENV", "CONDA_PREFIX"]
+        .into_iter()
+        .find_map(|key| env_hints.get(key))
+    else {
+        return Vec::new();
+    };
+    crate::spec_autogen::executables_in(&Path::new(prefix).join("bin"))
+        .into_iter()
+        .filter(|name| !name.contains('.') && !STOCK_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .collect()
 }
 
 // --- TSV helpers ---
@@ -381,33 +762,92 @@ fn sanitize_tsv(s: &str) -> Cow<'_, str> {
     }
 }
 
-fn print_error(message: &str) {
-    let sanitized = sanitize_tsv(message);
-    println!("error\t{sanitized}");
+/// Machine-readable error category, so the plugin can decide how loudly to
+/// report a failure without matching on message text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorCode {
+    InvalidRequest,
+    Offline,
+    LlmUnavailable,
+    LlmError,
+    RateLimited,
+    Timeout,
+    Empty,
+    Blocked,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl ErrorCode {
+    fn name(self) -> &'static str {
+        match self {
+            Self::InvalidRequest => "invalid_request",
+            Self::Offline => "offline",
+            Self::LlmUnavailable => "llm_unavailable",
+            Self::LlmError => "llm_error",
+            Self::RateLimited => "rate_limited",
+            Self::Timeout => "timeout",
+            Self::Empty => "empty",
+            Self::Blocked => "blocked",
+        }
+    }
 
-    #[test]
-    fn test_blocklist_substring_match() {
-        let bl = CompiledBlocklist::new(&["curl -u".into(), "export *=".into()]);
-        assert!(bl.is_blocked("curl -u admin:pass http://x"));
-        assert!(!bl.is_blocked("curl http://example.com"));
+    fn from_llm_error(error: &crate::llm::LlmError) -> Self {
+        use crate::llm::LlmError;
+        match error {
+            LlmError::Http(e) if e.is_timeout() => Self::Timeout,
+            LlmError::Http(e) if e.is_connect() => Self::LlmUnavailable,
+            LlmError::Api { status: 429, .. } => Self::RateLimited,
+            LlmError::BackoffActive => Self::LlmUnavailable,
+            LlmError::EmptyResponse => Self::Empty,
+            LlmError::Http(_) | LlmError::Api { .. } => Self::LlmError,
+        }
     }
+}
 
-    #[test]
-    fn test_blocklist_wildcard_pattern() {
-        let bl = CompiledBlocklist::new(&[r#"curl -H "Authorization*"#.into()]);
-        assert!(bl.is_blocked(r#"curl -H "Authorization: Bearer tok""#));
-        assert!(!bl.is_blocked("curl -H Accept"));
+/// Format an error as the TSV the plugin parses: error\t<message>\t<code>
+fn format_error(code: ErrorCode, message: &str) -> String {
+    format!("error\t{}\t{}", sanitize_tsv(message), code.name())
+}
+
+/// Report a failure: as an error line the plugin parses, or as a plain
+/// error when a person is reading.
+fn fail(output: Output, code: ErrorCode, message: &str) -> anyhow::Result<()> {
+    if output != Output::Tsv {
+        anyhow::bail!("{message}");
     }
+    println!("{}", format_error(code, message));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn test_blocklist_empty_and_whitespace() {
-        let bl = CompiledBlocklist::new(&["".into(), "  ".into()]);
-        assert!(!bl.is_blocked("anything"));
+    fn test_python_env_scripts_skip_stock_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let venv = tempfile::tempdir().unwrap();
+        let bin = venv.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+        for name in [
+            "pytest",
+            "black",
+            "python3",
+            "pip",
+            "activate",
+            "activate.fish",
+        ] {
+            std::fs::write(bin.join(name), "").unwrap();
+            std::fs::set_permissions(bin.join(name), std::fs::Permissions::from_mode(0o755))
+                .unwrap();
+        }
+
+        let hints = HashMap::from([(
+            "VIRTUAL_ENV".to_string(),
+            venv.path().to_string_lossy().into_owned(),
+        )]);
+        assert_eq!(python_env_scripts(&hints), vec!["black", "pytest"]);
+        assert!(python_env_scripts(&HashMap::new()).is_empty());
     }
 
     #[test]
@@ -424,51 +864,85 @@ mod tests {
     #[test]
     fn test_tsv_output_format() {
         // Verify the exact TSV wire format the plugin parses:
-        // list\t<count>\t<text>\t<source>\t<desc>\t<kind>\t...
+        // list\t<count>\t<text>\t<source>\t<desc>\t<kind>\t<risk>\t...
         let items = vec![
             crate::llm::NlTranslationItem {
                 command: "git status".into(),
-                warning: None,
+                risk: None,
             },
             crate::llm::NlTranslationItem {
-                command: "git stash".into(),
-                warning: Some("Stash changes".into()),
+                command: "git reset --hard".into(),
+                risk: crate::llm::assess_risk("git reset --hard"),
             },
         ];
 
-        let count = items.len();
-        let mut out = format!("list\t{count}");
-        for item in &items {
-            let desc = item.warning.as_deref().unwrap_or("");
-            out.push('\t');
-            out.push_str(&sanitize_tsv(&item.command));
-            out.push_str("\tllm\t");
-            out.push_str(&sanitize_tsv(desc));
-            out.push_str("\tcommand");
-        }
+        let durations = vec![crate::durations::CommandDuration {
+            command: "git status".into(),
+            last_secs: 12,
+            runs: 1,
+            ran_at: 0,
+        }];
+        let out = format_suggestion_list(&items, "llm", Path::new("/nonexistent"), &durations);
 
         // Parse it back the same way the plugin does (tab-split)
         let fields: Vec<&str> = out.split('\t').collect();
+        assert_eq!(fields.len(), 12);
         assert_eq!(fields[0], "list");
         assert_eq!(fields[1], "2");
-        // Item 0: text=git status, source=llm, desc="", kind=command
+        // Item 0: text=git status, source=llm, desc=last run time, kind=command, risk=""
         assert_eq!(fields[2], "git status");
         assert_eq!(fields[3], "llm");
-        assert_eq!(fields[4], "");
+        assert_eq!(fields[4], "~12s last time");
         assert_eq!(fields[5], "command");
-        // Item 1: text=git stash, source=llm, desc=Stash changes, kind=command
-        assert_eq!(fields[6], "git stash");
-        assert_eq!(fields[7], "llm");
-        assert_eq!(fields[8], "Stash changes");
-        assert_eq!(fields[9], "command");
+        assert_eq!(fields[6], "");
+        // Item 1: desc carries the rationale, risk the severity name
+        assert_eq!(fields[7], "git reset --hard");
+        assert_eq!(fields[8], "llm");
+        assert_eq!(fields[9], "discards uncommitted changes");
+        assert_eq!(fields[10], "command");
+        assert_eq!(fields[11], "high");
+    }
+
+    #[test]
+    fn test_tsv_plan_format() {
+        let steps = vec![
+            crate::llm::NlTranslationItem {
+                command: "python3 -m venv .venv".into(),
+                risk: None,
+            },
+            crate::llm::NlTranslationItem {
+                command: ".venv/bin/pip install -r requirements.txt".into(),
+                risk: None,
+            },
+            crate::llm::NlTranslationItem {
+                command: "rm -rf build".into(),
+                risk: crate::llm::assess_risk("rm -rf build"),
+            },
+        ];
+        let fields: Vec<String> = format_plan(&steps).split('\t').map(String::from).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "plan",
+                "3",
+                "python3 -m venv .venv",
+                "",
+                "",
+                ".venv/bin/pip install -r requirements.txt",
+                "",
+                "",
+                "rm -rf build",
+                "high",
+                "deletes files recursively; not recoverable"
+            ]
+        );
     }
 
     #[test]
     fn test_tsv_error_format() {
-        let msg = sanitize_tsv("bad request");
-        let out = format!("error\t{msg}");
+        let out = format_error(ErrorCode::InvalidRequest, "bad\trequest");
         let fields: Vec<&str> = out.split('\t').collect();
-        assert_eq!(fields, vec!["error", "bad request"]);
+        assert_eq!(fields, vec!["error", "bad    request", "invalid_request"]);
     }
 
     #[test]
@@ -2,7 +2,7 @@ use anyhow::{bail, Context as _};
 use std::path::PathBuf;
 
 const GITHUB_RELEASES_API: &str = "https://api.github.com/repos/colin-ho/synapse/releases/latest";
-const CHECK_INTERVAL_SECS: u64 = 86400; // 24 hours
+const CHECK_INTERVAL_SECS: u64 = 7 * 86400; // weekly
 
 type Version = (u64, u64, u64);
 
@@ -30,6 +30,9 @@ fn current_version() -> Version {
 struct VersionCache {
     latest: String,
     checked_at: u64,
+    /// Version already announced at shell startup.
+    #[serde(default)]
+    notified: Option<String>,
 }
 
 fn now_secs() -> u64 {
@@ -45,15 +48,37 @@ fn read_cache() -> Option<VersionCache> {
 }
 
 fn write_cache(latest: &str) {
-    let Some(path) = cache_path() else { return };
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
     let cache = VersionCache {
         latest: latest.to_string(),
         checked_at: now_secs(),
+        notified: read_cache().and_then(|c| c.notified),
     };
-    let _ = std::fs::write(path, serde_json::to_string(&cache).unwrap_or_default());
+    store_cache(&cache);
+}
+
+fn store_cache(cache: &VersionCache) {
+    let Some(path) = cache_path() else { return };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, serde_json::to_string(cache).unwrap_or_default());
+}
+
+/// When the last background check ran (Unix seconds), if ever.
+pub fn last_checked() -> Option<u64> {
+    read_cache().map(|c| c.checked_at)
+}
+
+/// The newer version to announce at shell startup, at most once per version.
+pub fn take_update_notice() -> Option<String> {
+    let latest = cached_update_available()?;
+    let mut cache = read_cache()?;
+    if cache.notified.as_deref() == Some(latest.as_str()) {
+        return None;
+    }
+    cache.notified = Some(latest.clone());
+    store_cache(&cache);
+    Some(latest)
 }
 
 /// Returns the latest version string if an update is available, based on cache only.
@@ -156,6 +181,15 @@ async fn do_update() -> anyhow::Result<()> {
         .context("failed to download release")?
         .bytes()
         .await?;
+    let checksum = reqwest::get(format!("{url}.sha256"))
+        .await?
+        .error_for_status()
+        .context("failed to download release checksum")?
+        .text()
+        .await?;
+    if !checksum_matches(&bytes, &checksum) {
+        bail!("checksum mismatch for {url}; not installing");
+    }
 
     let decoder = flate2::read::GzDecoder::new(&bytes[..]);
     let mut archive = tar::Archive::new(decoder);
@@ -192,11 +226,35 @@ async fn do_update() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Compare against a `sha256sum`-style line (`<hex>  <file>`).
+fn checksum_matches(bytes: &[u8], checksum: &str) -> bool {
+    use sha2::{Digest, Sha256};
+
+    let Some(expected) = checksum.split_whitespace().next() else {
+        return false;
+    };
+    let actual: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    actual.eq_ignore_ascii_case(expected)
+}
+
 pub async fn run(check: bool) -> anyhow::Result<()> {
+    let config = crate::config::Config::load();
+    let offline = config.is_offline();
     if check {
+        // Background checks are opt-in and stay silent when offline; the
+        // cached result is kept.
+        if offline || !config.check_updates {
+            return Ok(());
+        }
         check_and_cache().await;
         Ok(())
     } else {
+        if offline {
+            anyhow::bail!("offline mode is enabled (unset SYNAPSE_OFFLINE or set offline = false)");
+        }
         do_update().await
     }
 }
@@ -219,6 +277,15 @@ mod tests {
         assert!((1, 0, 0) > (0, 99, 99));
     }
 
+    #[test]
+    fn test_checksum_matches() {
+        let line =
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  synapse.tar.gz\n";
+        assert!(checksum_matches(b"hello", line));
+        assert!(!checksum_matches(b"hello!", line));
+        assert!(!checksum_matches(b"hello", ""));
+    }
+
     #[test]
     fn test_cached_update_no_cache() {
         // With no cache file, should return None
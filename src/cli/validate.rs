@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::spec_store::SpecStore;
+
+/// Print one warning per line for a command line about to run, or nothing.
+pub(super) async fn validate_command(command: String, cwd: Option<PathBuf>) -> anyhow::Result<()> {
+    let config = Config::load();
+    let command = command.trim().to_string();
+    let Some(program) = command.split_whitespace().next() else {
+        return Ok(());
+    };
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()?,
+    };
+
+    let spec_store = SpecStore::new(config.spec.clone());
+    if let Some(spec) = spec_store.lookup_with_system_fallback(program, &cwd).await {
+        for warning in crate::validate::validate(&spec, &command) {
+            println!("{warning}");
+        }
+    }
+    Ok(())
+}
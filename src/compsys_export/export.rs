@@ -1,17 +1,23 @@
-use crate::spec::{ArgSpec, CommandSpec, OptionSpec, SubcommandSpec};
+use crate::spec::{ArgSpec, CommandSpec, OptionSpec, SpecSource, SubcommandSpec};
 
-use super::format::{escape_zsh_string, format_arg, format_option};
+use super::format::{describe_entry, escape_case_pattern, format_arg, format_option, shell_quote};
+use super::EXPORT_VERSION;
+
+pub(super) fn source_label(source: SpecSource) -> &'static str {
+    match source {
+        SpecSource::Discovered => "discovered (parsed from --help)",
+        SpecSource::ProjectAuto => "project-auto",
+        SpecSource::Imported => "imported (spec bundle)",
+    }
+}
 
 pub(super) fn export_command_spec(spec: &CommandSpec) -> String {
     let mut out = String::new();
 
     out.push_str(&format!("#compdef {}\n", spec.name));
     out.push_str("# Auto-generated by synapse -- do not edit manually\n");
-    let source_label = match spec.source {
-        crate::spec::SpecSource::Discovered => "discovered (parsed from --help)",
-        crate::spec::SpecSource::ProjectAuto => "project-auto",
-    };
-    out.push_str(&format!("# Source: {source_label}\n"));
+    out.push_str(&format!("# Source: {}\n", source_label(spec.source)));
+    out.push_str(&format!("# Export version: {EXPORT_VERSION}\n"));
     out.push_str(&format!(
         "# Generated: {}\n",
         std::time::SystemTime::now()
@@ -21,7 +27,7 @@ pub(super) fn export_command_spec(spec: &CommandSpec) -> String {
     ));
     out.push_str("# Regenerate with: synapse generate-completions --force\n\n");
 
-    let fn_name = format!("_{}", spec.name.replace('-', "_"));
+    let fn_name = format!("_{}", fn_name_part(&spec.name));
 
     if spec.recursive {
         export_recursive_command(&mut out, &fn_name, &spec.options);
@@ -31,10 +37,17 @@ pub(super) fn export_command_spec(spec: &CommandSpec) -> String {
         export_subcommand_command(&mut out, &fn_name, spec);
     }
 
+    // The whole file is the autoloaded function, so this runs on every call.
+    if let Some(root) = &spec.project_root {
+        let root = shell_quote(&root.to_string_lossy());
+        out.push_str(&format!(
+            "\nif [[ \"$PWD/\" != {root}/* ]]; then\n    _default \"$@\"\n    return\nfi\n"
+        ));
+    }
     out.push_str(&format!("\n{fn_name} \"$@\"\n"));
 
     for alias in &spec.aliases {
-        out.push_str(&format!("compdef {fn_name} {alias}\n"));
+        out.push_str(&format!("compdef {fn_name} {}\n", shell_quote(alias)));
     }
 
     out
@@ -91,18 +104,23 @@ fn export_subcommand_command(out: &mut String, fn_name: &str, spec: &CommandSpec
 }
 
 fn subcommand_fn_name(parent_fn_name: &str, subcommand_name: &str) -> String {
-    format!("{parent_fn_name}_{}", subcommand_name.replace('-', "_"))
+    format!("{parent_fn_name}_{}", fn_name_part(subcommand_name))
+}
+
+/// Names like `build:prod` or `db.migrate` (Makefile targets, npm scripts)
+/// become `build_prod` in function names.
+fn fn_name_part(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
 }
 
 fn subcommand_pattern(sub: &SubcommandSpec) -> String {
-    if sub.aliases.is_empty() {
-        format!("({})", sub.name)
-    } else {
-        let all: Vec<&str> = std::iter::once(sub.name.as_str())
-            .chain(sub.aliases.iter().map(|alias| alias.as_str()))
-            .collect();
-        format!("({})", all.join("|"))
-    }
+    let all: Vec<String> = std::iter::once(&sub.name)
+        .chain(&sub.aliases)
+        .map(|name| escape_case_pattern(name))
+        .collect();
+    format!("({})", all.join("|"))
 }
 
 fn export_dispatch_command(
@@ -129,10 +147,9 @@ fn export_dispatch_command(
 
     for sub in subcommands {
         let desc = sub.description.as_deref().unwrap_or("");
-        let escaped_desc = escape_zsh_string(desc);
         out.push_str(&format!(
-            "                '{}:{}'\n",
-            sub.name, escaped_desc
+            "                '{}'\n",
+            describe_entry(&sub.name, desc)
         ));
     }
 
@@ -184,3 +201,47 @@ fn export_subcommand_fn(out: &mut String, fn_name: &str, sub: &SubcommandSpec) {
         out.push_str("}\n");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_script_completion_only_inside_project() {
+        let spec = CommandSpec {
+            name: "deploy.sh".into(),
+            options: vec![OptionSpec {
+                long: Some("--env".into()),
+                ..Default::default()
+            }],
+            project_root: Some("/work/my app".into()),
+            ..Default::default()
+        };
+        let out = export_command_spec(&spec);
+        let guard =
+            "if [[ \"$PWD/\" != '/work/my app'/* ]]; then\n    _default \"$@\"\n    return\nfi\n";
+        assert!(out.contains(guard), "{out}");
+        assert!(out.find(guard) < out.rfind("_deploy_sh \"$@\""), "{out}");
+
+        let global = CommandSpec {
+            project_root: None,
+            ..spec
+        };
+        assert!(!export_command_spec(&global).contains("_default"));
+    }
+
+    #[test]
+    fn test_aliases_are_quoted() {
+        let spec = CommandSpec {
+            name: "deployctl".into(),
+            aliases: vec!["dc".into(), "dc; touch pwned".into()],
+            ..Default::default()
+        };
+        let out = export_command_spec(&spec);
+        assert!(out.contains("compdef _deployctl dc\n"), "{out}");
+        assert!(
+            out.contains("compdef _deployctl 'dc; touch pwned'\n"),
+            "{out}"
+        );
+    }
+}
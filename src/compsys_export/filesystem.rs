@@ -2,10 +2,10 @@ use std::collections::HashSet;
 use std::io;
 use std::path::{Path, PathBuf};
 
-use crate::spec::CommandSpec;
+use crate::spec::{CommandSpec, SpecSource};
 
-use super::export::export_command_spec;
-use super::GenerationReport;
+use super::export::{export_command_spec, source_label};
+use super::{GeneratedFile, GenerationReport};
 
 pub(super) fn write_completion_file(spec: &CommandSpec, dir: &Path) -> io::Result<PathBuf> {
     std::fs::create_dir_all(dir)?;
@@ -85,3 +85,53 @@ pub(super) fn remove_stale_project_auto(
 
     Ok(removed)
 }
+
+pub(super) fn list_generated(dir: &Path) -> Vec<GeneratedFile> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(command) = file_name.to_str().and_then(|n| n.strip_prefix('_')) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let header: Vec<&str> = content.lines().take(6).collect();
+        if !header.contains(&"# Auto-generated by synapse -- do not edit manually") {
+            continue;
+        }
+
+        let source = [
+            SpecSource::Discovered,
+            SpecSource::ProjectAuto,
+            SpecSource::Imported,
+        ]
+        .into_iter()
+        .find(|source| {
+            header
+                .iter()
+                .any(|line| line.strip_prefix("# Source: ") == Some(source_label(*source)))
+        });
+        let Some(source) = source else {
+            continue;
+        };
+        let export_version = header
+            .iter()
+            .find_map(|line| line.strip_prefix("# Export version: "))
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0);
+
+        files.push(GeneratedFile {
+            command: command.to_string(),
+            path: entry.path(),
+            source,
+            export_version,
+        });
+    }
+    files.sort_by(|a, b| a.command.cmp(&b.command));
+    files
+}
@@ -1,4 +1,4 @@
-use crate::spec::{ArgSpec, ArgTemplate, GeneratorSpec, OptionSpec};
+use crate::spec::{ArgSpec, ArgTemplate, ArgValue, GeneratorFormat, GeneratorSpec, OptionSpec};
 
 pub(super) fn format_option(opt: &OptionSpec) -> String {
     let desc = opt
@@ -8,78 +8,133 @@ pub(super) fn format_option(opt: &OptionSpec) -> String {
         .unwrap_or_default();
 
     let arg_suffix = if opt.takes_arg {
-        if let Some(ref generator) = opt.arg_generator {
-            format!("::{}", format_generator_action(generator))
-        } else {
-            ": :".to_string()
-        }
+        let action = opt
+            .arg_template
+            .as_ref()
+            .map(template_action)
+            .or_else(|| value_list_action(&opt.arg_suggestions))
+            .or_else(|| opt.arg_generator.as_ref().map(format_generator_action))
+            .unwrap_or_else(|| " ".to_string());
+        format!(":value:{}", action.replace('\'', "'\\''"))
     } else {
         String::new()
     };
 
+    // A short/long pair excludes itself; `exclusive_with` adds other flags.
+    let exclusions = |own: &[&str]| -> String {
+        let all: Vec<&str> = own
+            .iter()
+            .copied()
+            .chain(opt.exclusive_with.iter().map(String::as_str))
+            .collect();
+        if all.is_empty() {
+            String::new()
+        } else {
+            format!("({})", escape_zsh_string(&all.join(" ")))
+        }
+    };
+
+    // Names come from bundles and `--help` output, so they are escaped too;
+    // in the brace form each alternative is quoted on its own.
     match (opt.short.as_deref(), opt.long.as_deref()) {
         (Some(short), Some(long)) => {
             let eq = if opt.takes_arg { "=" } else { "" };
-            format!("'({short} {long})'{{{short},{long}{eq}}}'[{desc}]{arg_suffix}'")
+            let excl = exclusions(&[short, long]);
+            let (short, long) = (escape_option_name(short), escape_option_name(long));
+            format!("'{excl}'{{'{short}','{long}{eq}'}}'[{desc}]{arg_suffix}'")
         }
         (None, Some(long)) => {
             let eq = if opt.takes_arg { "=" } else { "" };
-            format!("'{long}{eq}[{desc}]{arg_suffix}'")
+            let excl = exclusions(&[]);
+            let long = escape_option_name(long);
+            format!("'{excl}{long}{eq}[{desc}]{arg_suffix}'")
         }
         (Some(short), None) => {
-            format!("'{short}[{desc}]{arg_suffix}'")
+            let excl = exclusions(&[]);
+            let short = escape_option_name(short);
+            format!("'{excl}{short}[{desc}]{arg_suffix}'")
         }
         (None, None) => String::new(),
     }
 }
 
 pub(super) fn format_arg(arg: &ArgSpec) -> String {
-    let prefix = if arg.variadic { "*" } else { "" };
+    let prefix = if arg.variadic {
+        "*"
+    } else if arg.optional {
+        ":"
+    } else {
+        ""
+    };
 
     if let Some(ref template) = arg.template {
-        return match template {
-            ArgTemplate::FilePaths => format!("'{prefix}:file:_files'"),
-            ArgTemplate::Directories => format!("'{prefix}:directory:_files -/'"),
-            ArgTemplate::EnvVars => {
-                format!("'{prefix}:variable:_parameters -g \"*(export)\"'")
-            }
-            ArgTemplate::History => format!("'{prefix}:arg:'"),
+        let message = match template {
+            ArgTemplate::FilePaths | ArgTemplate::FileGlob(_) => "file",
+            ArgTemplate::Directories => "directory",
+            ArgTemplate::EnvVars => "variable",
+            ArgTemplate::History => "arg",
         };
+        let action = template_action(template).replace('\'', "'\\''");
+        return format!("'{prefix}:{message}:{action}'");
     }
 
-    if !arg.suggestions.is_empty() {
-        let values = arg
-            .suggestions
-            .iter()
-            .map(|suggestion| escape_zsh_string(suggestion))
-            .collect::<Vec<_>>()
-            .join(" ");
-        let name = if arg.name.is_empty() {
-            "arg"
-        } else {
-            &arg.name
-        };
-        return format!("'{prefix}:{name}:({values})'");
+    let name = if arg.name.is_empty() {
+        "arg".to_string()
+    } else {
+        escape_zsh_string(&arg.name).replace(':', "\\:")
+    };
+
+    if let Some(action) = value_list_action(&arg.suggestions) {
+        return format!("'{prefix}:{name}:{}'", action.replace('\'', "'\\''"));
     }
 
     if let Some(ref generator) = arg.generator {
         let action = format_generator_action(generator).replace('\'', "'\\''");
-        let name = if arg.name.is_empty() {
-            "arg"
-        } else {
-            &arg.name
-        };
         return format!("'{prefix}:{name}:{action}'");
     }
 
-    let name = if arg.name.is_empty() {
-        "arg"
-    } else {
-        &arg.name
-    };
     format!("'{prefix}:{name}:'")
 }
 
+fn template_action(template: &ArgTemplate) -> String {
+    match template {
+        ArgTemplate::FilePaths => "_files".to_string(),
+        ArgTemplate::FileGlob(glob) => {
+            format!("_files -g \"{}\"", escape_double_quote_string(glob))
+        }
+        ArgTemplate::Directories => "_files -/".to_string(),
+        ArgTemplate::EnvVars => "_parameters -g \"*(export)\"".to_string(),
+        ArgTemplate::History => String::new(),
+    }
+}
+
+/// `(a b c)`, or `((a\:desc ...))` when any value is described so that
+/// _arguments shows the descriptions. `None` for an empty list.
+fn value_list_action(values: &[ArgValue]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    let described = values.iter().any(|v| v.description.is_some());
+    let words = values
+        .iter()
+        .map(|v| {
+            let value = escape_action_word(&v.value);
+            match v.description.as_deref() {
+                Some(desc) => format!("{value}\\:{}", escape_action_word(desc)),
+                _ => value,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    // Brackets would otherwise be read as an option description.
+    let words = words.replace('[', "\\[").replace(']', "\\]");
+    Some(if described {
+        format!("(({words}))")
+    } else {
+        format!("({words})")
+    })
+}
+
 pub(super) fn format_generator_action(generator: &GeneratorSpec) -> String {
     let cmd_escaped = escape_double_quote_string(&generator.command);
     let mut synapse_cmd = format!("synapse run-generator \"{cmd_escaped}\" --cwd \"$PWD\"");
@@ -94,7 +149,15 @@ pub(super) fn format_generator_action(generator: &GeneratorSpec) -> String {
         synapse_cmd.push_str(&format!(" --split-on \"{split_escaped}\""));
     }
 
-    format!("{{local -a vals; vals=(${{(f)\"$({synapse_cmd} 2>/dev/null)\"}}); compadd -a vals}}")
+    // TSV generators print `value:description` entries for `_describe`.
+    let add = match generator.format {
+        GeneratorFormat::Lines => "compadd -a vals",
+        GeneratorFormat::Tsv => {
+            synapse_cmd.push_str(" --format tsv");
+            "_describe value vals"
+        }
+    };
+    format!("{{local -a vals; vals=(${{(f)\"$({synapse_cmd} 2>/dev/null)\"}}); {add}}}")
 }
 
 fn escape_double_quote_string(value: &str) -> String {
@@ -105,9 +168,178 @@ fn escape_double_quote_string(value: &str) -> String {
         .replace('`', "\\`")
 }
 
+/// Backslash-escape a word inside an `_arguments` `((...))` value list, where
+/// whitespace separates entries and `:` separates a value from its description.
+fn escape_action_word(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ':' | ' ' | '(' | ')' | '"' | '$' | '`') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Quote `value` as one zsh word: unchanged when it has no special
+/// characters, otherwise single-quoted.
+pub fn shell_quote(value: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+:,./-".contains(c);
+    if !value.is_empty() && value.chars().all(safe) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// A `name:description` entry for a `_describe` array inside single quotes;
+/// a colon in the name would otherwise start the description.
+pub(super) fn describe_entry(name: &str, description: &str) -> String {
+    format!(
+        "{}:{}",
+        escape_zsh_string(&name.replace(':', "\\:")),
+        escape_zsh_string(description)
+    )
+}
+
+/// Backslash-escape glob and syntax characters so `value` matches itself in
+/// a `case` pattern.
+pub(super) fn escape_case_pattern(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if !(c.is_alphanumeric()
+            || matches!(c, '-' | '_' | '.' | ':' | '/' | '@' | '+' | '=' | ','))
+        {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// An option name inside a single-quoted `_arguments` spec, where `:` would
+/// end the name.
+fn escape_option_name(name: &str) -> String {
+    escape_zsh_string(name).replace(':', "\\:")
+}
+
 pub(super) fn escape_zsh_string(value: &str) -> String {
     value
         .replace('\'', "'\\''")
         .replace('[', "\\[")
         .replace(']', "\\]")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_option_exclusions_and_values() {
+        let json = OptionSpec {
+            short: Some("-j".into()),
+            long: Some("--json".into()),
+            description: Some("JSON output".into()),
+            exclusive_with: vec!["--yaml".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            format_option(&json),
+            "'(-j --json --yaml)'{'-j','--json'}'[JSON output]'"
+        );
+
+        let format = OptionSpec {
+            long: Some("--format".into()),
+            takes_arg: true,
+            arg_suggestions: vec![
+                ArgValue {
+                    value: "json".into(),
+                    description: Some("machine readable".into()),
+                },
+                ArgValue {
+                    value: "table".into(),
+                    description: None,
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            format_option(&format),
+            "'--format=[]:value:((json\\:machine\\ readable table))'"
+        );
+    }
+
+    #[test]
+    fn test_quoting_of_unusual_names() {
+        assert_eq!(shell_quote("src/main.rs"), "src/main.rs");
+        assert_eq!(shell_quote("my file"), "'my file'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(
+            describe_entry("build:prod", "it's"),
+            "build\\:prod:it'\\''s"
+        );
+        assert_eq!(escape_case_pattern("test:*[x]"), "test:\\*\\[x\\]");
+
+        let arg = ArgSpec {
+            name: "host:port".into(),
+            ..Default::default()
+        };
+        assert_eq!(format_arg(&arg), "':host\\:port:'");
+
+        let optional = ArgSpec {
+            name: "pr".into(),
+            optional: true,
+            ..Default::default()
+        };
+        assert_eq!(format_arg(&optional), "'::pr:'");
+    }
+
+    #[test]
+    fn test_names_cannot_break_out_of_quotes() {
+        let option = OptionSpec {
+            long: Some("--x'$(touch pwned)'".into()),
+            description: Some("harmless".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_option(&option),
+            r"'--x'\''$(touch pwned)'\''[harmless]'"
+        );
+
+        let pair = OptionSpec {
+            short: Some("-a".into()),
+            long: Some("--b',$(id),'".into()),
+            ..Default::default()
+        };
+        assert!(format_option(&pair).contains(r"{'-a','--b'\'',$(id),'\'''}"));
+
+        let arg = ArgSpec {
+            name: "f'$(id)'".into(),
+            ..Default::default()
+        };
+        assert_eq!(format_arg(&arg), r"':f'\''$(id)'\'':'");
+    }
+
+    #[test]
+    fn test_format_arg_file_glob() {
+        let arg = ArgSpec {
+            name: "vars".into(),
+            template: Some(ArgTemplate::FileGlob("*.tfvars".into())),
+            ..Default::default()
+        };
+        assert_eq!(format_arg(&arg), "':file:_files -g \"*.tfvars\"'");
+    }
+
+    #[test]
+    fn test_format_tsv_generator_uses_describe() {
+        let generator = GeneratorSpec {
+            command: "git branch --format='%(refname:short)%09%(subject)'".into(),
+            format: GeneratorFormat::Tsv,
+            ..Default::default()
+        };
+        let action = format_generator_action(&generator);
+        assert!(action.contains(" --format tsv 2>/dev/null"), "{action}");
+        assert!(action.ends_with("; _describe value vals}"), "{action}");
+    }
+}
@@ -4,12 +4,19 @@ use std::collections::HashSet;
 use std::io;
 use std::path::{Path, PathBuf};
 
-use crate::spec::CommandSpec;
+use crate::spec::{CommandSpec, SpecSource};
 
 mod export;
 mod filesystem;
 mod format;
 
+/// Version of the generated file format, recorded in each file's header.
+/// Bump it when the output changes so `synapse completions sync` rewrites
+/// files generated by older builds.
+pub const EXPORT_VERSION: u32 = 5;
+
+pub use format::shell_quote;
+
 pub fn completions_dir() -> PathBuf {
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("/tmp"))
@@ -28,6 +35,21 @@ pub struct GenerationReport {
     pub removed: Vec<String>,
 }
 
+/// A completion file previously written by synapse.
+#[derive(Debug)]
+pub struct GeneratedFile {
+    pub command: String,
+    pub path: PathBuf,
+    pub source: SpecSource,
+    /// 0 for files written before the header recorded a version.
+    pub export_version: u32,
+}
+
+/// Every synapse-generated completion file in `dir`, identified by header.
+pub fn list_generated(dir: &Path) -> Vec<GeneratedFile> {
+    filesystem::list_generated(dir)
+}
+
 pub fn generate_all(
     specs: &[CommandSpec],
     existing_commands: &HashSet<String>,
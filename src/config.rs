@@ -11,13 +11,31 @@ pub const NL_MIN_QUERY_LENGTH: usize = 5;
 pub const GENERATOR_TIMEOUT_MS: u64 = 5_000;
 /// Timeout in ms for each --help invocation during discovery.
 pub const DISCOVER_TIMEOUT_MS: u64 = 2_000;
+/// Max time in ms to wait for a keychain lookup (a locked keychain may
+/// otherwise wait for an unlock prompt).
+pub const KEYCHAIN_TIMEOUT_MS: u64 = 1_500;
 #[derive(Debug, Default, Deserialize, Clone)]
 #[serde(default)]
 pub struct Config {
+    /// Disable every network-touching feature (remote LLM endpoints, update
+    /// checks). `SYNAPSE_OFFLINE=1` turns this on for the current shell.
+    pub offline: bool,
+    /// Check GitHub releases weekly for a newer synapse (opt-in).
+    pub check_updates: bool,
+    pub general: GeneralConfig,
     pub spec: SpecConfig,
     pub security: SecurityConfig,
     pub llm: LlmConfig,
     pub completions: CompletionsConfig,
+    pub memory: MemoryConfig,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default)]
+pub struct GeneralConfig {
+    /// Language for risk warnings and LLM replies (e.g. "de"). Defaults to
+    /// the locale from `LC_ALL`/`LC_MESSAGES`/`LANG`.
+    pub language: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -30,12 +48,101 @@ pub struct SpecConfig {
     pub discover_from_help: bool,
     /// Commands to never run --help on
     pub discover_blocklist: Vec<String>,
+    /// Complete package names for `npm install` from the local npm cache
+    pub registry_completions: bool,
+    /// Allow `synapse cloud` listings that call cloud APIs (AWS regions, S3
+    /// buckets, GCP projects). Profiles and kube contexts are always local.
+    pub cloud_network_completions: bool,
+    /// Check commands against their spec on Enter; on a likely typo the
+    /// plugin shows a warning and runs the command on a second Enter
+    pub validate_on_enter: bool,
+    /// Shared spec sources synced by `synapse spec sync` (and daily in the
+    /// background at shell startup).
+    pub remote_sources: Vec<RemoteSpecSource>,
+    /// Directory globs (`~` expanded, subdirectories included) whose
+    /// project-local scripts may be run with `--help` during `synapse scan`.
+    pub trusted_projects: Vec<String>,
+}
+
+/// One `[[spec.remote_sources]]` entry: set exactly one of `git` or `index`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RemoteSpecSource {
+    /// Git repository whose top-level `*.toml` files are spec bundles.
+    pub git: Option<String>,
+    /// Full commit hash to check out, or a tag with `verify_tag` (git
+    /// sources only).
+    pub rev: Option<String>,
+    /// Treat `rev` as a tag and require `git verify-tag` to accept its
+    /// signature (gpg keyring or `gpg.ssh.allowedSignersFile`).
+    pub verify_tag: bool,
+    /// HTTPS URL of an index listing `[[bundle]]` entries (`url`, `sha256`).
+    pub index: Option<String>,
+    /// Keep the bundles' generator commands (they run at completion time).
+    pub trust_generators: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct SecurityConfig {
+    /// Shorthand deny rules: glob patterns (or plain substrings) that block a
+    /// suggestion from any source in any directory. Checked after `policy`.
     pub command_blocklist: Vec<String>,
+    /// Per-tool suggestion sources, checked before `policy`.
+    pub source_rules: Vec<SourceRule>,
+    /// Ordered allow/deny rules; the first matching rule decides.
+    pub policy: Vec<PolicyRule>,
+    /// Append blocked suggestions (secrets scrubbed) to ~/.synapse/policy-audit.log
+    pub audit_log: bool,
+    /// Require a second Enter to accept NL suggestions at or above this risk
+    /// severity ("low", "medium", "high", "critical", or "off").
+    pub confirm_risk: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyAction {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyMatch {
+    /// `*` and `?` wildcards; a pattern without wildcards matches as a substring.
+    #[default]
+    Glob,
+    Regex,
+}
+
+/// One `[[security.source_rules]]` entry: which suggestion sources may
+/// suggest commands that invoke `command` (e.g. no `llm` for `kubectl`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceRule {
+    /// Program name, matched against every command in a pipeline or list.
+    pub command: String,
+    /// Sources never allowed for this command.
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// If set, the only sources allowed for this command.
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+}
+
+/// One `[[security.policy]]` rule.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyRule {
+    pub action: PolicyAction,
+    /// Pattern matched against the full command line.
+    pub pattern: String,
+    #[serde(default, rename = "match")]
+    pub match_kind: PolicyMatch,
+    /// Only apply to suggestions from this source (e.g. "llm").
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Only apply when the working directory is at or below this glob (`~` expanded).
+    #[serde(default)]
+    pub directory: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -52,17 +159,62 @@ pub struct LlmConfig {
     pub nl_max_suggestions: usize,
     /// Temperature for NL suggestions (lower = more deterministic).
     pub temperature: f32,
+    /// Include the last N lines of the current tmux pane (scrubbed) in NL
+    /// context. 0 disables the capture.
+    pub tmux_context_lines: usize,
+    /// Approximate token budget for the cwd file list and known flags in NL
+    /// context. Lower it for small local models.
+    pub context_tokens: usize,
+    /// Number of similar past translations (accepted and run successfully)
+    /// to include as few-shot examples. 0 disables recording and retrieval.
+    pub few_shot_examples: usize,
+    /// Log every prompt and raw response (scrubbed) to
+    /// `~/.synapse/llm-audit.jsonl`, viewable with `synapse audit llm`.
+    pub audit_log: bool,
 }
 
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(default)]
 pub struct CompletionsConfig {
     /// Override the output directory for generated completions
     pub output_dir: Option<String>,
+    /// Run `synapse scan` in the background when the plugin enters a new
+    /// directory, so project completions are ready before the first TAB
+    pub scan_on_cd: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Record branches, containers, paths, and hosts from executed commands
+    /// and share them across terminals (used as NL translation context),
+    /// plus the values passed to flags (offered by `synapse suggest`)
+    pub enabled: bool,
+    /// Record how long slow commands take, noted on NL suggestions
+    /// ("~45s last time")
+    pub durations: bool,
 }
 
 // --- Defaults ---
 
+impl Default for CompletionsConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: None,
+            scan_on_cd: true,
+        }
+    }
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            durations: true,
+        }
+    }
+}
+
 impl Default for SpecConfig {
     fn default() -> Self {
         Self {
@@ -71,6 +223,11 @@ impl Default for SpecConfig {
             scan_depth: 3,
             discover_from_help: true,
             discover_blocklist: Vec::new(),
+            registry_completions: false,
+            cloud_network_completions: false,
+            validate_on_enter: false,
+            remote_sources: Vec::new(),
+            trusted_projects: Vec::new(),
         }
     }
 }
@@ -83,6 +240,10 @@ impl Default for SecurityConfig {
                 "curl -u".into(),
                 r#"curl -H "Authorization*"#.into(),
             ],
+            source_rules: Vec::new(),
+            policy: Vec::new(),
+            audit_log: true,
+            confirm_risk: "high".into(),
         }
     }
 }
@@ -97,19 +258,46 @@ impl Default for LlmConfig {
             timeout_ms: 10_000,
             nl_max_suggestions: 3,
             temperature: 0.3,
+            tmux_context_lines: 0,
+            context_tokens: 600,
+            few_shot_examples: 3,
+            audit_log: false,
         }
     }
 }
 
 // --- Methods ---
 
+impl SecurityConfig {
+    /// The `confirm_risk` severity, or `None` when set to "off". A value
+    /// that is not a severity falls back to "high" with a warning rather
+    /// than turning confirmation off.
+    pub fn confirm_threshold(&self) -> Option<crate::llm::Severity> {
+        if self.confirm_risk == "off" {
+            return None;
+        }
+        crate::llm::Severity::from_name(&self.confirm_risk).or_else(|| {
+            eprintln!(
+                "[synapse] Unknown security.confirm_risk \"{}\" (expected low, medium, high, critical or off); using \"high\"",
+                self.confirm_risk
+            );
+            Some(crate::llm::Severity::High)
+        })
+    }
+}
+
 impl Config {
-    pub fn load() -> Self {
-        let config_path = std::env::var("XDG_CONFIG_HOME")
+    /// Location of the config file, whether or not it exists.
+    pub fn path() -> PathBuf {
+        std::env::var("XDG_CONFIG_HOME")
             .ok()
             .map(|d| PathBuf::from(d).join("synapse").join("config.toml"))
             .or_else(|| dirs::config_dir().map(|d| d.join("synapse").join("config.toml")))
-            .unwrap_or_else(|| PathBuf::from("~/.config/synapse/config.toml"));
+            .unwrap_or_else(|| PathBuf::from("~/.config/synapse/config.toml"))
+    }
+
+    pub fn load() -> Self {
+        let config_path = Self::path();
 
         if config_path.exists() {
             match std::fs::read_to_string(&config_path) {
@@ -127,4 +315,27 @@ impl Config {
 
         Config::default()
     }
+
+    /// Whether offline mode is on, via config or `SYNAPSE_OFFLINE`.
+    pub fn is_offline(&self) -> bool {
+        self.offline || std::env::var("SYNAPSE_OFFLINE").is_ok_and(|v| !v.is_empty() && v != "0")
+    }
+
+    /// The `general.language` key, else the locale of this process.
+    pub fn language(&self) -> crate::i18n::Language {
+        crate::i18n::Language::resolve(self.general.language.as_deref(), |key| {
+            std::env::var(key).ok()
+        })
+    }
+
+    /// Whether offline mode rules out the configured LLM endpoint (only
+    /// local endpoints stay usable offline).
+    pub fn llm_blocked_offline(&self) -> bool {
+        self.is_offline()
+            && !self
+                .llm
+                .base_url
+                .as_deref()
+                .is_some_and(crate::llm::is_local_base_url)
+    }
 }
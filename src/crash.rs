@@ -0,0 +1,115 @@
+//! Crash reports for panics.
+//!
+//! The plugin runs most synapse commands in the background with output
+//! discarded, so a panic would otherwise leave no trace. The hook writes the
+//! version, platform, subcommand name (never its arguments), panic message
+//! and backtrace to `~/.synapse/crash/`, keeping the newest [`MAX_REPORTS`].
+//! `synapse crash last` prints the most recent one.
+
+use std::io;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+
+const MAX_REPORTS: usize = 10;
+
+pub fn crash_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("crash"))
+}
+
+/// Write a report for every panic, then run the default hook. `command` is
+/// the subcommand being run, recorded without its arguments.
+pub fn install_hook(command: Option<String>) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(dir) = crash_dir() {
+            let report = format_report(info, command.as_deref());
+            if let Ok(path) = write_report(&dir, &report) {
+                eprintln!("synapse: crash report written to {}", path.display());
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+fn format_report(info: &PanicHookInfo<'_>, command: Option<&str>) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(non-string panic payload)".to_string());
+    let location = info
+        .location()
+        .map_or_else(|| "unknown".to_string(), ToString::to_string);
+    format!(
+        "synapse {} crashed\nos: {} {}\ncommand: {}\npanic: {message}\nlocation: {location}\n\nbacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        command.unwrap_or("(none)"),
+        std::backtrace::Backtrace::force_capture(),
+    )
+}
+
+/// Save `report` as `<unix secs>-<pid>.txt` and drop the oldest reports
+/// beyond [`MAX_REPORTS`].
+fn write_report(dir: &Path, report: &str) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = dir.join(format!("{now}-{}.txt", std::process::id()));
+    std::fs::write(&path, report)?;
+
+    let mut reports = list(dir);
+    while reports.len() > MAX_REPORTS {
+        let _ = std::fs::remove_file(reports.remove(0));
+    }
+    Ok(path)
+}
+
+/// Report files, oldest first.
+fn list(dir: &Path) -> Vec<PathBuf> {
+    let mut reports: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            let secs = stem.split('-').next()?.parse().ok()?;
+            Some((secs, path))
+        })
+        .collect();
+    reports.sort();
+    reports.into_iter().map(|(_, path)| path).collect()
+}
+
+/// The most recent report and its path.
+pub fn last(dir: &Path) -> Option<(PathBuf, String)> {
+    let path = list(dir).pop()?;
+    let report = std::fs::read_to_string(&path).ok()?;
+    Some((path, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_are_pruned_and_last_is_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for secs in 0..MAX_REPORTS + 2 {
+            std::fs::write(dir.path().join(format!("{secs}-1.txt")), "old").unwrap();
+        }
+        let written = write_report(dir.path(), "synapse crashed").unwrap();
+
+        assert_eq!(list(dir.path()).len(), MAX_REPORTS);
+        assert!(!dir.path().join("0-1.txt").exists());
+        assert_eq!(
+            last(dir.path()),
+            Some((written, "synapse crashed".to_string()))
+        );
+    }
+}
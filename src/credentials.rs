@@ -0,0 +1,138 @@
+//! API keys stored in the OS keychain instead of environment variables.
+//!
+//! Keys live under service `synapse`, with the key's name as the account,
+//! which by default is the `[llm] api_key_env` name (e.g. `OPENAI_API_KEY`).
+//! A provider name such as `openai` stands for that provider's variable.
+//! macOS uses the login keychain through `security`; Linux uses the Secret
+//! Service (GNOME Keyring, KWallet) through libsecret's `secret-tool`.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+
+const SERVICE: &str = "synapse";
+
+/// The environment-variable-style name keys are stored under: `openai`
+/// becomes `OPENAI_API_KEY`, while `OPENAI_API_KEY` or `MY_TOKEN` stay as
+/// they are.
+pub fn key_name(name: &str) -> String {
+    if name.chars().any(|c| c.is_ascii_lowercase()) && !name.contains('_') {
+        format!("{}_API_KEY", name.to_ascii_uppercase().replace('-', "_"))
+    } else {
+        name.to_string()
+    }
+}
+
+/// The stored key named `name`, if a keychain is available and holds one.
+/// Gives up after [`crate::config::KEYCHAIN_TIMEOUT_MS`], so a locked
+/// keychain cannot stall a translation.
+pub fn get(name: &str) -> Option<String> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("security");
+        command.args(["find-generic-password", "-s", SERVICE, "-a", name, "-w"]);
+        command
+    } else {
+        let mut command = Command::new("secret-tool");
+        command.args(["lookup", "service", SERVICE, "account", name]);
+        command
+    };
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let deadline = Instant::now() + Duration::from_millis(crate::config::KEYCHAIN_TIMEOUT_MS);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    if !status.success() {
+        return None;
+    }
+    let mut secret = String::new();
+    child.stdout.take()?.read_to_string(&mut secret).ok()?;
+    let secret = secret.trim().to_string();
+    (!secret.is_empty()).then_some(secret)
+}
+
+/// Store `secret` under `name`, replacing any existing key. The secret is
+/// passed on stdin, never on a command line other processes can read.
+pub fn set(name: &str, secret: &str) -> anyhow::Result<()> {
+    if secret.is_empty() || secret.contains(|c: char| c.is_whitespace() || c == '"') {
+        bail!("API keys cannot be empty or contain whitespace or quotes");
+    }
+    let (program, args, input) = if cfg!(target_os = "macos") {
+        // `security -i` reads commands from stdin.
+        let command =
+            format!("add-generic-password -U -s {SERVICE} -a \"{name}\" -w \"{secret}\"\n");
+        ("security", vec!["-i".to_string()], command)
+    } else {
+        let args = ["store", "--label", &format!("synapse {name}")]
+            .into_iter()
+            .map(str::to_string)
+            .chain(["service", SERVICE, "account", name].map(str::to_string))
+            .collect();
+        ("secret-tool", args, secret.to_string())
+    };
+
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .with_context(|| format!("cannot run {program}; is a keychain available?"))?;
+    child
+        .stdin
+        .take()
+        .context("keychain stdin unavailable")?
+        .write_all(input.as_bytes())?;
+    if !child.wait()?.success() {
+        bail!("{program} failed to store the key");
+    }
+    Ok(())
+}
+
+/// Delete the key named `name`. Returns false if there was none.
+pub fn remove(name: &str) -> anyhow::Result<bool> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["delete-generic-password", "-s", SERVICE, "-a", name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .context("cannot run security")?
+    } else {
+        if get(name).is_none() {
+            return Ok(false);
+        }
+        Command::new("secret-tool")
+            .args(["clear", "service", SERVICE, "account", name])
+            .status()
+            .context("cannot run secret-tool; is libsecret installed?")?
+    };
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_names_map_to_their_variable() {
+        assert_eq!(key_name("openai"), "OPENAI_API_KEY");
+        assert_eq!(key_name("open-router"), "OPEN_ROUTER_API_KEY");
+        assert_eq!(key_name("OPENAI_API_KEY"), "OPENAI_API_KEY");
+        assert_eq!(key_name("my_token"), "my_token");
+    }
+}
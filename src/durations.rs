@@ -0,0 +1,134 @@
+//! How long slow commands took when they last ran.
+//!
+//! The plugin times each command between `preexec` and `precmd` and records
+//! runs of at least [`MIN_RECORDED_SECS`] via `synapse durations record`.
+//! NL suggestions for a recorded command then note e.g. `~45s last time`.
+//! Command lines are scrubbed of likely secrets before they are stored.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of commands retained on disk.
+const MAX_ENTRIES: usize = 500;
+
+/// Shorter runs are not recorded (the plugin applies the same cutoff).
+pub const MIN_RECORDED_SECS: u64 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandDuration {
+    pub command: String,
+    /// Wall-clock seconds of the most recent recorded run.
+    pub last_secs: u64,
+    /// Number of recorded runs.
+    pub runs: u32,
+    /// Unix timestamp (seconds) of the most recent recorded run.
+    pub ran_at: u64,
+}
+
+pub fn durations_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("durations.json"))
+}
+
+/// Record a run of `command`, most recent first.
+pub fn record(path: &Path, command: &str, secs: u64) -> io::Result<()> {
+    let command = crate::llm::scrub_secrets(command.trim());
+    if command.is_empty() || secs < MIN_RECORDED_SECS {
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut entries = load(path);
+    let runs = match entries.iter().position(|e| e.command == command) {
+        Some(i) => entries.remove(i).runs + 1,
+        None => 1,
+    };
+    entries.insert(
+        0,
+        CommandDuration {
+            command,
+            last_secs: secs,
+            runs,
+            ran_at: now,
+        },
+    );
+    entries.truncate(MAX_ENTRIES);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write-then-rename so concurrent terminals never observe a torn file.
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string(&entries)?)?;
+    std::fs::rename(tmp, path)
+}
+
+/// Load recorded durations, most recent first. A missing or unreadable store
+/// is empty.
+pub fn load(path: &Path) -> Vec<CommandDuration> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// A note such as `~45s last time` if `command` has a recorded run.
+pub fn note(entries: &[CommandDuration], command: &str) -> Option<String> {
+    let command = crate::llm::scrub_secrets(command.trim());
+    let entry = entries.iter().find(|e| e.command == command)?;
+    Some(format!("~{} last time", format_secs(entry.last_secs)))
+}
+
+/// Compact duration: `45s`, `3m`, `1h 5m`.
+pub fn format_secs(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", (s + 30) / 60),
+        s => {
+            let minutes = (s % 3600) / 60;
+            if minutes == 0 {
+                format!("{}h", s / 3600)
+            } else {
+                format!("{}h {minutes}m", s / 3600)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_latest_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("durations.json");
+        record(&path, "cargo build --release", 95).unwrap();
+        record(&path, "npm test", 40).unwrap();
+        record(&path, "cargo build --release", 45).unwrap();
+        record(&path, "ls", 0).unwrap();
+
+        let entries = load(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "cargo build --release");
+        assert_eq!(entries[0].runs, 2);
+        assert_eq!(
+            note(&entries, "cargo build --release").as_deref(),
+            Some("~45s last time")
+        );
+        assert_eq!(note(&entries, "ls"), None);
+    }
+
+    #[test]
+    fn test_format_secs() {
+        assert_eq!(format_secs(45), "45s");
+        assert_eq!(format_secs(150), "3m");
+        assert_eq!(format_secs(3600), "1h");
+        assert_eq!(format_secs(3900), "1h 5m");
+    }
+}
@@ -0,0 +1,179 @@
+//! One-line summaries of commands, shown in the NL dropdown on request.
+//!
+//! A summary is built from spec descriptions when the command's subcommand is
+//! described by a spec. Otherwise the LLM is asked, and its answer is cached
+//! in `~/.synapse/explain_cache.json` so repeated lookups are instant. Cached
+//! commands are scrubbed of likely secrets, both when stored and looked up.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::spec::{CommandSpec, OptionSpec};
+
+/// Maximum number of cached LLM summaries.
+const MAX_CACHED: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedSummary {
+    command: String,
+    summary: String,
+}
+
+pub fn cache_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("explain_cache.json"))
+}
+
+/// Cached summary for `command`, if any.
+pub fn cached(path: &Path, command: &str) -> Option<String> {
+    let command = crate::llm::scrub_secrets(command);
+    load(path)
+        .into_iter()
+        .find(|c| c.command == command)
+        .map(|c| c.summary)
+}
+
+/// Store a summary, newest first.
+pub fn store(path: &Path, command: &str, summary: &str) -> io::Result<()> {
+    let command = crate::llm::scrub_secrets(command);
+    let mut entries = load(path);
+    entries.retain(|c| c.command != command);
+    entries.insert(
+        0,
+        CachedSummary {
+            command,
+            summary: summary.to_string(),
+        },
+    );
+    entries.truncate(MAX_CACHED);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write-then-rename so concurrent terminals never observe a torn file.
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string(&entries)?)?;
+    std::fs::rename(tmp, path)
+}
+
+fn load(path: &Path) -> Vec<CachedSummary> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Summarize a simple command (no pipes or chaining) from its spec: the
+/// deepest described subcommand, plus descriptions of any flags used.
+/// Returns `None` when no subcommand description is available.
+pub fn summarize_from_spec(spec: &CommandSpec, command: &str) -> Option<String> {
+    if command.contains(['|', ';', '&', '`']) || command.contains("$(") {
+        return None;
+    }
+
+    let mut tokens = command.split_whitespace();
+    if tokens.next()? != spec.name {
+        return None;
+    }
+
+    let mut path = vec![spec.name.as_str()];
+    let mut description = None;
+    let mut subcommands = &spec.subcommands;
+    let mut options: Vec<&OptionSpec> = spec.options.iter().collect();
+    let mut flags = Vec::new();
+
+    for token in tokens {
+        if token.starts_with('-') {
+            flags.push(token.split('=').next().unwrap_or(token));
+            continue;
+        }
+        let Some(sub) = subcommands
+            .iter()
+            .find(|s| s.name == token || s.aliases.iter().any(|a| a == token))
+        else {
+            continue;
+        };
+        path.push(sub.name.as_str());
+        description = sub.description.as_deref().or(description);
+        subcommands = &sub.subcommands;
+        options.extend(sub.options.iter());
+    }
+
+    let mut summary = format!("{}: {}", path.join(" "), description?);
+    for flag in flags {
+        let described = options.iter().find_map(|opt| {
+            let matches = opt.long.as_deref() == Some(flag) || opt.short.as_deref() == Some(flag);
+            matches.then_some(opt.description.as_deref()).flatten()
+        });
+        if let Some(desc) = described {
+            summary.push_str(&format!("; {flag}: {desc}"));
+        }
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::SubcommandSpec;
+
+    fn docker_spec() -> CommandSpec {
+        CommandSpec {
+            name: "docker".into(),
+            subcommands: vec![SubcommandSpec {
+                name: "compose".into(),
+                subcommands: vec![SubcommandSpec {
+                    name: "up".into(),
+                    description: Some("Create and start containers".into()),
+                    options: vec![OptionSpec {
+                        short: Some("-d".into()),
+                        long: Some("--detach".into()),
+                        description: Some("Run in the background".into()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize_from_spec() {
+        let spec = docker_spec();
+        assert_eq!(
+            summarize_from_spec(&spec, "docker compose up -d web").as_deref(),
+            Some("docker compose up: Create and start containers; -d: Run in the background")
+        );
+        assert!(summarize_from_spec(&spec, "docker compose up | tee log").is_none());
+        assert!(summarize_from_spec(&spec, "docker ps").is_none());
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("explain_cache.json");
+        assert!(cached(&path, "ls -la").is_none());
+        store(&path, "ls -la", "Lists all files with details").unwrap();
+        assert_eq!(
+            cached(&path, "ls -la").as_deref(),
+            Some("Lists all files with details")
+        );
+    }
+
+    #[test]
+    fn test_cache_never_stores_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("explain_cache.json");
+        let command = "TOKEN=hunter2hunter2 deployctl rollout";
+        store(&path, command, "Rolls out the service").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("hunter2hunter2"), "got: {content}");
+        assert_eq!(
+            cached(&path, command).as_deref(),
+            Some("Rolls out the service")
+        );
+    }
+}
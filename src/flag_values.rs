@@ -0,0 +1,273 @@
+//! Values the user has passed to specific flags (`kubectl -n prod`,
+//! `ssh -p 2222`), learned from executed commands.
+//!
+//! `synapse memory record` parses each command against its spec, so only
+//! flags the spec says take an argument are remembered, keyed by the flag's
+//! long form. `synapse suggest` offers the most used values for a flag ahead
+//! of the spec's static ones. Values of secret-named flags (`--password`,
+//! `--token`), values that look like secrets and shell expansions are never
+//! stored.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::spec::{CommandSpec, OptionSpec};
+
+/// Maximum number of (command, flag, value) entries retained on disk.
+const MAX_ENTRIES: usize = 500;
+
+/// Flags whose name contains one of these take secrets, whatever the value
+/// looks like.
+const SECRET_FLAG_WORDS: &[&str] = &["password", "passwd", "secret", "token", "key", "credential"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagValue {
+    pub command: String,
+    /// Long form when the spec has one (`--namespace`), else the short form.
+    pub flag: String,
+    pub value: String,
+    pub count: u32,
+    /// Unix timestamp (seconds) of the most recent use.
+    pub seen_at: u64,
+}
+
+pub fn flag_values_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("flag_values.json"))
+}
+
+/// The key a flag's values are stored under.
+pub fn flag_key(opt: &OptionSpec) -> Option<&str> {
+    opt.long.as_deref().or(opt.short.as_deref())
+}
+
+/// `(flag, value)` pairs for the value-taking flags in `command`.
+pub fn extract(spec: &CommandSpec, command: &str) -> Vec<(String, String)> {
+    if command.contains(['|', ';', '&', '`', '<', '>']) || command.contains("$(") {
+        return Vec::new();
+    }
+    let mut tokens = command.split_whitespace();
+    if tokens.next() != Some(spec.name.as_str()) {
+        return Vec::new();
+    }
+
+    let mut options: Vec<&OptionSpec> = spec.options.iter().collect();
+    let mut subcommands = &spec.subcommands;
+    let mut pending: Option<&OptionSpec> = None;
+    let mut pairs = Vec::new();
+    for token in tokens {
+        if token == "--" {
+            break;
+        }
+        if let Some(opt) = pending.take() {
+            pairs.extend(pair(opt, token));
+            continue;
+        }
+        if let Some((name, value)) = token.split_once('=').filter(|_| token.starts_with("--")) {
+            if let Some(opt) = options.iter().find(|o| o.long.as_deref() == Some(name)) {
+                pairs.extend(pair(opt, value));
+            }
+            continue;
+        }
+        if token.starts_with('-') {
+            pending = options
+                .iter()
+                .find(|o| o.long.as_deref() == Some(token) || o.short.as_deref() == Some(token))
+                .copied()
+                .filter(|o| o.takes_arg);
+            continue;
+        }
+        let sub = subcommands
+            .iter()
+            .find(|s| s.name == token || s.aliases.iter().any(|a| a == token));
+        if let Some(sub) = sub {
+            options.extend(sub.options.iter());
+            subcommands = &sub.subcommands;
+        }
+    }
+    pairs
+}
+
+fn pair(opt: &OptionSpec, value: &str) -> Option<(String, String)> {
+    let flag = flag_key(opt)?;
+    let secret_flag = [opt.long.as_deref(), opt.short.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(str::to_ascii_lowercase)
+        .any(|name| SECRET_FLAG_WORDS.iter().any(|word| name.contains(word)));
+    let value = value.trim_matches(['"', '\'']);
+    let expands = value.is_empty() || value.contains(['$', '*', '?']);
+    let assignment = format!("{flag}={value}");
+    if secret_flag || expands || crate::llm::scrub_secrets(&assignment) != assignment {
+        return None;
+    }
+    Some((flag.to_string(), value.to_string()))
+}
+
+/// Count one use of each pair for `command`, most recent first.
+pub fn record(path: &Path, command: &str, pairs: &[(String, String)]) -> io::Result<()> {
+    if pairs.is_empty() {
+        return Ok(());
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let _lock = crate::records::lock_store(path)?;
+    let mut entries = load(path);
+    for (flag, value) in pairs {
+        let existing = entries
+            .iter()
+            .position(|e| e.command == command && &e.flag == flag && &e.value == value);
+        let count = existing.map_or(0, |i| entries.remove(i).count);
+        entries.insert(
+            0,
+            FlagValue {
+                command: command.to_string(),
+                flag: flag.clone(),
+                value: value.clone(),
+                count: count + 1,
+                seen_at: now,
+            },
+        );
+    }
+    entries.truncate(MAX_ENTRIES);
+
+    // Write-then-rename so concurrent terminals never observe a torn file.
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string(&entries)?)?;
+    std::fs::rename(tmp, path)
+}
+
+/// Load remembered values, most recent first. A missing or unreadable store
+/// is empty.
+pub fn load(path: &Path) -> Vec<FlagValue> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Remembered values of `flag` for `command`, most used first (ties go to
+/// the most recent).
+pub fn values_for<'a>(entries: &'a [FlagValue], command: &str, flag: &str) -> Vec<&'a FlagValue> {
+    let mut values: Vec<&FlagValue> = entries
+        .iter()
+        .filter(|e| e.command == command && e.flag == flag)
+        .collect();
+    values.sort_by_key(|e| std::cmp::Reverse(e.count));
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> CommandSpec {
+        toml::from_str(
+            r#"
+            name = "kubectl"
+            [[options]]
+            long = "--namespace"
+            short = "-n"
+            takes_arg = true
+            [[options]]
+            long = "--token"
+            takes_arg = true
+            [[subcommands]]
+            name = "logs"
+            [[subcommands.options]]
+            long = "--follow"
+            short = "-f"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_extract_uses_spec_arity() {
+        let spec = spec();
+        assert_eq!(
+            extract(&spec, "kubectl logs -f -n prod api"),
+            vec![("--namespace".to_string(), "prod".to_string())]
+        );
+        assert_eq!(
+            extract(&spec, "kubectl --namespace=staging logs api"),
+            vec![("--namespace".to_string(), "staging".to_string())]
+        );
+        assert!(extract(&spec, "kubectl -n $NS logs api").is_empty());
+        assert!(extract(
+            &spec,
+            "kubectl --token sk-abcdef1234567890abcdefgh get pods"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_secret_flags_are_never_remembered() {
+        let mysql: CommandSpec = toml::from_str(
+            r#"
+            name = "mysql"
+            [[options]]
+            long = "--password"
+            short = "-p"
+            takes_arg = true
+            [[options]]
+            long = "--user"
+            short = "-u"
+            takes_arg = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            extract(&mysql, "mysql -u app --password hunter2"),
+            vec![("--user".to_string(), "app".to_string())]
+        );
+        assert!(extract(&mysql, "mysql -p hunter2").is_empty());
+        assert!(extract(&mysql, "mysql --password=hunter2").is_empty());
+
+        let curl: CommandSpec = toml::from_str(
+            r#"
+            name = "curl"
+            [[options]]
+            long = "--token"
+            takes_arg = true
+            [[options]]
+            long = "--api-key"
+            takes_arg = true
+            [[options]]
+            long = "--client-secret"
+            takes_arg = true
+            "#,
+        )
+        .unwrap();
+        for command in [
+            "curl --token abc123 https://example.com",
+            "curl --api-key abc123 https://example.com",
+            "curl --client-secret=abc123 https://example.com",
+        ] {
+            assert!(extract(&curl, command).is_empty(), "{command}");
+        }
+    }
+
+    #[test]
+    fn test_values_ranked_by_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flag_values.json");
+        let prod = [("--namespace".to_string(), "prod".to_string())];
+        let dev = [("--namespace".to_string(), "dev".to_string())];
+        record(&path, "kubectl", &prod).unwrap();
+        record(&path, "kubectl", &prod).unwrap();
+        record(&path, "kubectl", &dev).unwrap();
+
+        let entries = load(&path);
+        let values: Vec<&str> = values_for(&entries, "kubectl", "--namespace")
+            .iter()
+            .map(|e| e.value.as_str())
+            .collect();
+        assert_eq!(values, vec!["prod", "dev"]);
+        assert!(values_for(&entries, "kubectl", "--context").is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Localization of the user-visible text synapse writes itself (risk
+//! warnings on NL suggestions) and the language LLM replies should use.
+//!
+//! The language comes from the `general.language` config key, falling back
+//! to the POSIX locale (`LC_ALL`, `LC_MESSAGES`, `LANG`).
+
+use crate::llm::Rationale;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+    French,
+}
+
+impl Language {
+    /// Parse a language code or locale name: `de`, `de-AT`, `de_DE.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let lang = code
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match lang.as_str() {
+            "en" | "c" | "posix" => Some(Self::English),
+            "de" => Some(Self::German),
+            "es" => Some(Self::Spanish),
+            "fr" => Some(Self::French),
+            _ => None,
+        }
+    }
+
+    /// The configured language, else the first locale variable that is set.
+    pub fn resolve(configured: Option<&str>, env: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(language) = configured.and_then(Self::from_code) {
+            return language;
+        }
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(env)
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+            .unwrap_or_default()
+    }
+
+    /// English name, as used in LLM prompts.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "German",
+            Self::Spanish => "Spanish",
+            Self::French => "French",
+        }
+    }
+}
+
+/// The text of a risk rationale in `language`. The match is exhaustive, so
+/// a new [`Rationale`] does not compile until it is translated.
+pub fn localize(language: Language, reason: Rationale) -> &'static str {
+    use Rationale::*;
+    let (german, spanish, french) = match reason {
+        FormatsFilesystem => (
+            "formatiert das Dateisystem",
+            "formatea el sistema de archivos",
+            "formate le système de fichiers",
+        ),
+        OverwritesBlockDevice => (
+            "überschreibt ein Blockgerät",
+            "sobrescribe un dispositivo de bloques",
+            "écrase un périphérique bloc",
+        ),
+        DeletesFromRoot => (
+            "löscht ab dem Wurzelverzeichnis",
+            "borra desde la raíz del sistema",
+            "supprime depuis la racine",
+        ),
+        RawDiskWrite => (
+            "schreibt direkt auf die Festplatte",
+            "escritura directa en disco",
+            "écriture brute sur le disque",
+        ),
+        ShredsFiles => (
+            "überschreibt Dateiinhalte; nicht wiederherstellbar",
+            "sobrescribe datos de archivos; irrecuperable",
+            "écrase les données; irrécupérable",
+        ),
+        DeletesRecursively => (
+            "löscht Dateien rekursiv; nicht wiederherstellbar",
+            "borra archivos recursivamente; irrecuperable",
+            "supprime des fichiers récursivement; irrécupérable",
+        ),
+        DiscardsUncommitted => (
+            "verwirft nicht committete Änderungen",
+            "descarta cambios sin confirmar",
+            "annule les modifications non validées",
+        ),
+        DeletesUntracked => (
+            "löscht nicht versionierte Dateien",
+            "borra archivos sin seguimiento",
+            "supprime les fichiers non suivis",
+        ),
+        RewritesRemoteHistory => (
+            "schreibt die entfernte Historie um",
+            "reescribe el historial remoto",
+            "réécrit l'historique distant",
+        ),
+        WorldWritable => (
+            "macht Dateien für alle beschreibbar",
+            "permite escribir a todos",
+            "rend les fichiers modifiables par tous",
+        ),
+        FindDelete => (
+            "löscht Dateien (find -delete)",
+            "borra archivos (find -delete)",
+            "supprime des fichiers (find -delete)",
+        ),
+        DeletesFiles => ("löscht Dateien", "borra archivos", "supprime des fichiers"),
+        RemovesDirectories => (
+            "entfernt Verzeichnisse",
+            "elimina directorios",
+            "supprime des répertoires",
+        ),
+        TruncatesFile => (
+            "kürzt eine Datei",
+            "trunca un archivo",
+            "tronque un fichier",
+        ),
+        ChmodRecursive => (
+            "ändert Rechte rekursiv",
+            "cambia permisos recursivamente",
+            "modifie les droits récursivement",
+        ),
+        ForceKills => (
+            "beendet einen Prozess zwangsweise",
+            "mata un proceso a la fuerza",
+            "tue un processus de force",
+        ),
+        KillsByName => (
+            "beendet Prozesse nach Name",
+            "mata procesos por nombre",
+            "tue des processus par nom",
+        ),
+        DiscardsFileChanges => (
+            "verwirft Dateiänderungen",
+            "descarta cambios en archivos",
+            "annule les modifications de fichiers",
+        ),
+        OverwritesFile => (
+            "überschreibt eine Datei",
+            "sobrescribe un archivo",
+            "écrase un fichier",
+        ),
+    };
+    match language {
+        Language::English => reason.english(),
+        Language::German => german,
+        Language::Spanish => spanish,
+        Language::French => french,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefers_config_then_locale() {
+        let env = |key: &str| match key {
+            "LANG" => Some("de_DE.UTF-8".to_string()),
+            "LC_ALL" => Some(String::new()),
+            _ => None,
+        };
+        assert_eq!(Language::resolve(None, env), Language::German);
+        assert_eq!(Language::resolve(Some("fr"), env), Language::French);
+        assert_eq!(Language::resolve(Some("xx"), env), Language::German);
+        assert_eq!(Language::resolve(None, |_| None), Language::English);
+        assert_eq!(Language::from_code("es-MX"), Some(Language::Spanish));
+        assert_eq!(Language::from_code("C.UTF-8"), Some(Language::English));
+    }
+
+    #[test]
+    fn test_every_risk_rationale_is_translated() {
+        for &reason in Rationale::ALL {
+            for language in [Language::German, Language::Spanish, Language::French] {
+                let text = localize(language, reason);
+                assert!(!text.is_empty() && text != reason.english(), "{reason:?}");
+            }
+            assert_eq!(localize(Language::English, reason), reason.english());
+        }
+
+        let risk = crate::llm::assess_risk("git reset --hard").unwrap();
+        assert_eq!(
+            localize(Language::German, risk.reason),
+            "verwirft nicht committete Änderungen"
+        );
+    }
+}
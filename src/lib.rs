@@ -1,9 +1,28 @@
 pub mod cli;
 pub mod compsys_export;
 pub mod config;
+pub mod crash;
+pub mod credentials;
+pub mod durations;
+pub mod explain;
+pub mod flag_values;
+pub mod i18n;
 pub mod llm;
+pub mod memory;
+pub mod nl_examples;
+pub mod pins;
+pub mod preview;
 pub mod project;
+pub mod providers;
+pub mod records;
+pub mod security;
+pub mod snippets;
 pub mod spec;
 pub mod spec_autogen;
+pub mod spec_bundle;
 pub mod spec_store;
+pub mod spec_sync;
+pub mod stamp;
+pub mod suggest;
+pub mod validate;
 pub mod zsh_completion;
@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
@@ -8,9 +9,11 @@ use tokio::sync::Mutex;
 use crate::config::LlmConfig;
 
 use super::prompt::{
-    build_nl_prompt, NlTranslationContext, NlTranslationItem, NlTranslationResult,
+    build_nl_prompt, build_summary_prompt, NlTranslationContext, NlTranslationItem,
+    NlTranslationResult, MAX_PLAN_STEPS,
 };
-use super::response::{detect_destructive_command, extract_commands};
+use super::response::{extract_commands, split_plan};
+use super::risk::assess_risk;
 
 #[derive(Debug, thiserror::Error)]
 pub enum LlmError {
@@ -35,10 +38,14 @@ pub struct LlmClient {
     /// Set on API errors, cleared after 5 minutes.
     backoff_active: AtomicBool,
     backoff_until: Mutex<Option<Instant>>,
+    /// Where every exchange is logged, when `audit_log` is enabled.
+    audit_path: Option<PathBuf>,
 }
 
 impl LlmClient {
-    /// Construct an LlmClient from config. Returns `None` if disabled or API key is unset.
+    /// Construct an LlmClient from config. The API key comes from the OS
+    /// keychain, else the `api_key_env` variable. Returns `None` if disabled
+    /// or no key is set.
     pub fn from_config(config: &LlmConfig) -> Option<Self> {
         if !config.enabled {
             return None;
@@ -51,8 +58,10 @@ impl LlmClient {
             .map(|v| v.trim_end_matches('/').to_string())
             .filter(|v| !v.is_empty());
 
-        let api_key = match std::env::var(&config.api_key_env) {
-            Ok(v) if !v.is_empty() => v,
+        let api_key = match crate::credentials::get(&config.api_key_env)
+            .or_else(|| std::env::var(&config.api_key_env).ok())
+        {
+            Some(v) if !v.is_empty() => v,
             _ => {
                 // For local OpenAI-compatible endpoints (LM Studio, etc.), allow a placeholder.
                 if base_url.as_deref().is_some_and(is_local_base_url) {
@@ -77,6 +86,10 @@ impl LlmClient {
             rate_limiter: Mutex::new(Instant::now() - Duration::from_secs(1)),
             backoff_active: AtomicBool::new(false),
             backoff_until: Mutex::new(None),
+            audit_path: config
+                .audit_log
+                .then(super::audit::audit_log_path)
+                .flatten(),
         })
     }
 
@@ -142,7 +155,11 @@ impl LlmClient {
         let response_text = self
             .request_completion_raw(messages, max_tokens, Some(temperature))
             .await?;
-        let commands = extract_commands(&response_text, max_suggestions);
+        // A multi-step answer is an ordered plan, not a set of alternatives.
+        let (is_plan, commands) = match split_plan(&response_text) {
+            Some(steps) => (true, extract_commands(steps, MAX_PLAN_STEPS)),
+            None => (false, extract_commands(&response_text, max_suggestions)),
+        };
         if commands.is_empty() {
             return Err(LlmError::EmptyResponse);
         }
@@ -150,12 +167,43 @@ impl LlmClient {
         let items = commands
             .into_iter()
             .map(|command| NlTranslationItem {
-                warning: detect_destructive_command(&command),
+                risk: assess_risk(&command),
                 command,
             })
             .collect();
 
-        Ok(NlTranslationResult { items })
+        Ok(NlTranslationResult { items, is_plan })
+    }
+
+    /// Summarize a shell command as one plain-English sentence.
+    pub async fn summarize_command(
+        &self,
+        command: &str,
+        language: crate::i18n::Language,
+    ) -> Result<String, LlmError> {
+        let (system_prompt, user_prompt) = build_summary_prompt(command, language);
+        let messages = vec![
+            OpenAIMessage {
+                role: "system".to_string(),
+                content: system_prompt,
+            },
+            OpenAIMessage {
+                role: "user".to_string(),
+                content: user_prompt,
+            },
+        ];
+
+        let response_text = self.request_completion_raw(messages, 64, Some(0.2)).await?;
+        let summary = response_text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("")
+            .to_string();
+        if summary.is_empty() {
+            return Err(LlmError::EmptyResponse);
+        }
+        Ok(summary)
     }
 
     async fn request_completion_raw(
@@ -167,7 +215,23 @@ impl LlmClient {
         self.check_backoff().await?;
         self.rate_limit().await;
 
+        // Scrub the whole prompt (query, recent commands, few-shot examples)
+        // once, so the audit log records exactly what is sent.
+        let messages: Vec<OpenAIMessage> = messages
+            .into_iter()
+            .map(|m| OpenAIMessage {
+                content: super::scrub_secrets(&m.content),
+                ..m
+            })
+            .collect();
+        let audited = self
+            .audit_path
+            .as_ref()
+            .map(|path| (path, messages.clone()));
         let result = self.call_openai(messages, max_tokens, temperature).await;
+        if let Some((path, messages)) = audited {
+            self.audit(path, messages, &result);
+        }
         if result
             .as_ref()
             .err()
@@ -179,6 +243,22 @@ impl LlmClient {
         result
     }
 
+    fn audit(&self, path: &Path, messages: Vec<OpenAIMessage>, result: &Result<String, LlmError>) {
+        let exchange = super::audit::Exchange {
+            at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            endpoint: self.openai_chat_completions_url(),
+            model: self.model.clone(),
+            messages: messages.into_iter().map(|m| (m.role, m.content)).collect(),
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(ToString::to_string),
+        };
+        // Auditing must never break translation.
+        let _ = super::audit::record(path, exchange);
+    }
+
     fn should_activate_backoff(error: &LlmError) -> bool {
         matches!(error, LlmError::Api { status, .. } if *status == 429 || *status >= 500)
     }
@@ -311,7 +391,8 @@ fn url_with_v1_path(base_url: &str, suffix: &str) -> String {
     }
 }
 
-fn is_local_base_url(base_url: &str) -> bool {
+/// Whether `base_url` points at this machine (reachable without network).
+pub fn is_local_base_url(base_url: &str) -> bool {
     let lower = base_url.to_ascii_lowercase();
     let host_part = lower
         .split_once("://")
@@ -321,3 +402,87 @@ fn is_local_base_url(base_url: &str) -> bool {
         || host_part.starts_with("localhost")
         || host_part.starts_with("[::1]")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    /// Serve one chat completion on 127.0.0.1 and return the request body.
+    fn serve_once(listener: std::net::TcpListener) -> std::thread::JoinHandle<String> {
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let response = r#"{"choices":[{"message":{"content":"Rolls out the service"}}]}"#;
+            write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+            String::from_utf8(body).unwrap()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_audit_logs_the_scrubbed_request_that_was_sent() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit_path = dir.path().join("llm-audit.jsonl");
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+
+        let client = LlmClient {
+            api_key: "lm-studio".into(),
+            base_url: Some(base_url),
+            model: "local".into(),
+            client: Client::builder().no_proxy().build().unwrap(),
+            rate_limiter: Mutex::new(Instant::now() - Duration::from_secs(1)),
+            rate_limit_duration: Duration::ZERO,
+            backoff_active: AtomicBool::new(false),
+            backoff_until: Mutex::new(None),
+            audit_path: Some(audit_path.clone()),
+        };
+        let summary = client
+            .summarize_command(
+                "TOKEN=hunter2hunter2 deployctl rollout",
+                crate::i18n::Language::English,
+            )
+            .await
+            .unwrap();
+        assert_eq!(summary, "Rolls out the service");
+
+        let sent: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        let sent: Vec<(String, String)> = sent["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| {
+                (
+                    m["role"].as_str().unwrap().to_string(),
+                    m["content"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert!(sent
+            .iter()
+            .all(|(_, content)| !content.contains("hunter2hunter2")));
+        let logged = super::super::audit::tail(&audit_path, 1);
+        assert_eq!(logged[0].messages, sent);
+    }
+}
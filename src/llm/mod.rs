@@ -1,6 +1,11 @@
+pub mod audit;
 mod client;
 mod prompt;
 mod response;
+mod risk;
+mod scrub;
 
-pub use client::LlmClient;
-pub use prompt::{NlTranslationContext, NlTranslationItem};
+pub use client::{is_local_base_url, LlmClient, LlmError};
+pub use prompt::{NlTranslationContext, NlTranslationItem, RelevantSpec};
+pub use risk::{assess_risk, Rationale, Risk, Severity};
+pub use scrub::scrub_secrets;
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::i18n::Language;
+
 pub struct NlTranslationContext {
     pub query: String,
     pub cwd: String,
@@ -12,19 +14,40 @@ pub struct NlTranslationContext {
     pub project_commands: HashMap<String, Vec<String>>,
     /// Top-level entries in the working directory.
     pub cwd_entries: Vec<String>,
-    /// Known flags for tools mentioned in the query.
-    pub relevant_specs: HashMap<String, Vec<String>>,
+    /// Known subcommands and flags for tools mentioned in the query.
+    pub relevant_specs: HashMap<String, RelevantSpec>,
+    /// Recently used entities from entity memory, e.g. "branch feature-x".
+    pub recent_entities: Vec<String>,
+    /// Scrubbed tail of the current terminal pane (tmux), if captured.
+    pub terminal_output: Option<String>,
+    /// Similar past (query, command) pairs the user accepted and ran.
+    pub few_shot_examples: Vec<(String, String)>,
+    /// Approximate token budget shared by the file list and known flags.
+    pub context_tokens: usize,
+    /// The user's language; the request may be written in it.
+    pub language: Language,
+}
+
+#[derive(Debug, Default)]
+pub struct RelevantSpec {
+    pub subcommands: Vec<String>,
+    pub flags: Vec<String>,
 }
 
 pub struct NlTranslationItem {
     pub command: String,
-    pub warning: Option<String>,
+    pub risk: Option<super::Risk>,
 }
 
 pub struct NlTranslationResult {
     pub items: Vec<NlTranslationItem>,
+    /// Items are ordered steps of one plan rather than alternatives.
+    pub is_plan: bool,
 }
 
+/// Maximum steps accepted in a multi-step plan.
+pub const MAX_PLAN_STEPS: usize = 8;
+
 /// Build NL translation prompt as (system_message, user_message).
 pub fn build_nl_prompt(
     ctx: &NlTranslationContext,
@@ -32,16 +55,19 @@ pub fn build_nl_prompt(
     max_suggestions: usize,
 ) -> (String, String) {
     let system = if max_suggestions <= 1 {
-        "You are a shell command generator. Convert the user's natural language request into a single shell command.\n\n\
+        format!(
+            "You are a shell command generator. Convert the user's natural language request into a single shell command.\n\n\
          Rules:\n\
          - Return ONLY the shell command, nothing else\n\
          - Use tools available on the system (prefer common POSIX utilities)\n\
          - Use the working directory context (don't use absolute paths unless necessary)\n\
          - If the request is ambiguous, prefer the most common interpretation\n\
          - If the request requires multiple commands, chain them with && or |\n\
+         - If the request is a multi-step task where each step should succeed before the next runs (e.g. create a venv, then install requirements), instead reply with a line PLAN followed by up to {max_steps} numbered steps\n\
          - Never generate destructive commands (rm -rf /, dd, mkfs) without explicit safeguards\n\
-         - For file operations, prefer relative paths from the working directory"
-            .to_string()
+         - For file operations, prefer relative paths from the working directory",
+            max_steps = MAX_PLAN_STEPS,
+        )
     } else {
         format!(
             "You are a shell command generator. Convert the user's natural language request into {n} alternative shell commands, ranked from most likely to least likely.\n\n\
@@ -53,9 +79,11 @@ pub fn build_nl_prompt(
              - Use tools available on the system (prefer common POSIX utilities)\n\
              - Use the working directory context (don't use absolute paths unless necessary)\n\
              - If the request requires multiple commands, chain them with && or |\n\
+             - If the request is a multi-step task where each step should succeed before the next runs (e.g. create a venv, then install requirements), instead reply with a line PLAN followed by up to {max_steps} numbered steps (one command per step, no alternatives)\n\
              - Never generate destructive commands (rm -rf /, dd, mkfs) without explicit safeguards\n\
              - For file operations, prefer relative paths from the working directory",
             n = max_suggestions,
+            max_steps = MAX_PLAN_STEPS,
         )
     };
 
@@ -69,6 +97,10 @@ pub fn build_nl_prompt(
         ctx.project_type.as_deref().unwrap_or("unknown")
     ));
 
+    if ctx.language != Language::English {
+        user.push_str(&format!("- User language: {}\n", ctx.language.name()));
+    }
+
     if let Some(ref branch) = ctx.git_branch {
         user.push_str(&format!("- Git branch: {branch}\n"));
     }
@@ -90,21 +122,58 @@ pub fn build_nl_prompt(
         }
     }
 
+    // Files and specs share the context budget: half for files, the rest
+    // split evenly across tools. Entries matching the query are packed first.
+    let terms = query_terms(&ctx.query);
+    let file_budget = if ctx.relevant_specs.is_empty() {
+        ctx.context_tokens
+    } else {
+        ctx.context_tokens / 2
+    };
+
     if !ctx.cwd_entries.is_empty() {
-        let entries: Vec<_> = ctx.cwd_entries.iter().take(50).cloned().collect();
+        let entries = pack_by_relevance(&ctx.cwd_entries, &terms, file_budget);
         user.push_str(&format!("- Files in cwd: {}\n", entries.join(", ")));
     }
 
     if !ctx.relevant_specs.is_empty() {
-        for (tool, flags) in &ctx.relevant_specs {
-            let flags_str: Vec<_> = flags.iter().take(20).cloned().collect();
-            user.push_str(&format!(
-                "- Known flags for `{tool}`: {}\n",
-                flags_str.join(", ")
-            ));
+        let per_tool = (ctx.context_tokens - file_budget) / ctx.relevant_specs.len();
+        for (tool, spec) in &ctx.relevant_specs {
+            let flag_budget = if spec.subcommands.is_empty() {
+                per_tool
+            } else {
+                per_tool / 2
+            };
+            let subcommands = pack_by_relevance(&spec.subcommands, &terms, per_tool - flag_budget);
+            if !subcommands.is_empty() {
+                user.push_str(&format!(
+                    "- Known subcommands for `{tool}`: {}\n",
+                    subcommands.join(", ")
+                ));
+            }
+            let flags = pack_by_relevance(&spec.flags, &terms, flag_budget);
+            if !flags.is_empty() {
+                user.push_str(&format!(
+                    "- Known flags for `{tool}`: {}\n",
+                    flags.join(", ")
+                ));
+            }
         }
     }
 
+    if !ctx.recent_entities.is_empty() {
+        user.push_str(&format!(
+            "- Recently used: {}\n",
+            ctx.recent_entities.join(", ")
+        ));
+    }
+
+    if let Some(ref output) = ctx.terminal_output {
+        user.push_str("- Recent terminal output:\n```\n");
+        user.push_str(output);
+        user.push_str("\n```\n");
+    }
+
     if ctx.recent_commands.is_empty() {
         user.push_str("- Recent commands: (none)\n");
     } else {
@@ -114,7 +183,90 @@ pub fn build_nl_prompt(
         }
     }
 
+    if !ctx.few_shot_examples.is_empty() {
+        user.push_str("\nPast requests and the commands the user ran for them:\n");
+        for (query, command) in &ctx.few_shot_examples {
+            user.push_str(&format!("- \"{query}\" -> {command}\n"));
+        }
+    }
+
     user.push_str(&format!("\nUser request: {}", ctx.query));
 
     (system, user)
 }
+
+/// Build the command summary prompt as (system_message, user_message).
+pub fn build_summary_prompt(command: &str, language: Language) -> (String, String) {
+    let system = format!(
+        "You explain shell commands. Describe what the given command does in one short plain {} sentence (at most 15 words).\n\n\
+         Rules:\n\
+         - Reply with the sentence only, no code, no preamble\n\
+         - Mention anything destructive or irreversible the command does",
+        language.name()
+    );
+    (system, format!("Command: {command}"))
+}
+
+/// Rough token count (~4 characters per token, the usual BPE average for
+/// English and code). Errs high for short strings so packing stays conservative.
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4).max(1)
+}
+
+/// Lowercased query words worth matching against file and flag names.
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Pick items to fit `budget` tokens, preferring those that mention a query
+/// term and otherwise keeping the original order.
+fn pack_by_relevance<'a>(items: &'a [String], terms: &[String], budget: usize) -> Vec<&'a str> {
+    let mut ranked: Vec<&String> = items.iter().collect();
+    ranked.sort_by_key(|item| {
+        let lower = item.to_lowercase();
+        !terms.iter().any(|t| lower.contains(t.as_str()))
+    });
+
+    let mut used = 0;
+    let mut packed = Vec::new();
+    for item in ranked {
+        // +1 for the ", " separator.
+        let cost = estimate_tokens(item) + 1;
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        packed.push(item.as_str());
+    }
+    packed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_prefers_query_matches() {
+        let items: Vec<String> = ["Cargo.toml", "README.md", "docker-compose.yml", "src/"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let terms = query_terms("start the docker stack");
+        let packed = pack_by_relevance(&items, &terms, 10);
+        assert_eq!(packed, vec!["docker-compose.yml", "Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_pack_respects_budget() {
+        let items: Vec<String> = (0..100).map(|i| format!("--flag-{i}")).collect();
+        let packed = pack_by_relevance(&items, &[], 30);
+        assert!(!packed.is_empty());
+        let cost: usize = packed.iter().map(|f| estimate_tokens(f) + 1).sum();
+        assert!(cost <= 30);
+        assert_eq!(packed[0], "--flag-0");
+    }
+}
@@ -1,37 +1,13 @@
-/// Check if a command contains potentially destructive operations.
-/// Returns a warning description if so.
-///
-/// Uses simple substring matching — these are user-facing warnings, not security gates
-/// (the blocklist handles actual blocking). Simple checks are more robust and catch
-/// cases like `sudo rm` that position-anchored regexes would miss.
-pub fn detect_destructive_command(command: &str) -> Option<String> {
-    let patterns: &[(&str, &str)] = &[
-        ("rm ", "deletes files"),
-        ("rmdir ", "removes directories"),
-        ("dd ", "raw disk write"),
-        ("mkfs", "formats filesystem"),
-        ("truncate ", "truncates file"),
-        ("shred ", "overwrites file data"),
-        ("pkill ", "kills processes by name"),
-        ("chmod 777", "makes files world-writable"),
-        ("chmod -R", "changes permissions recursively"),
-        ("kill -9", "force-kills process"),
-        ("-delete", "deletes files (find -delete)"),
-    ];
-
-    for (pattern, description) in patterns {
-        if command.contains(pattern) {
-            return Some(description.to_string());
-        }
-    }
-
-    if let Some(pos) = command.find("> ") {
-        if pos == 0 || command.as_bytes()[pos - 1] != b'>' {
-            return Some("overwrites file".to_string());
-        }
-    }
-
-    None
+/// If the response is a multi-step plan (a `PLAN` marker line before the
+/// steps), return the text after the marker.
+pub fn split_plan(response: &str) -> Option<&str> {
+    let trimmed = response.trim_start().trim_start_matches("```").trim_start();
+    let (first, rest) = trimmed.split_once('\n')?;
+    let marker = first
+        .trim()
+        .trim_matches(['*', '#', ' '])
+        .trim_end_matches(':');
+    marker.eq_ignore_ascii_case("plan").then_some(rest)
 }
 
 /// Extract multiple shell commands from an LLM response.
@@ -134,3 +110,19 @@ fn strip_numeric_prefix(line: &str) -> &str {
         line
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_plan() {
+        let steps =
+            split_plan("PLAN:\n1. python3 -m venv .venv\n2. pip install -r req.txt").unwrap();
+        assert_eq!(
+            extract_commands(steps, 8),
+            vec!["python3 -m venv .venv", "pip install -r req.txt"]
+        );
+        assert!(split_plan("1. ls -la\n2. ls -l").is_none());
+    }
+}
@@ -0,0 +1,220 @@
+/// How much damage a command can do, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// Numeric level used by the plugin (1 = low .. 4 = critical; 0 = none).
+    pub fn level(self) -> u8 {
+        self as u8 + 1
+    }
+}
+
+/// Why a command is risky. Each reason has an English text here and its
+/// translations in [`crate::i18n::localize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rationale {
+    FormatsFilesystem,
+    OverwritesBlockDevice,
+    DeletesFromRoot,
+    RawDiskWrite,
+    ShredsFiles,
+    DeletesRecursively,
+    DiscardsUncommitted,
+    DeletesUntracked,
+    RewritesRemoteHistory,
+    WorldWritable,
+    FindDelete,
+    DeletesFiles,
+    RemovesDirectories,
+    TruncatesFile,
+    ChmodRecursive,
+    ForceKills,
+    KillsByName,
+    DiscardsFileChanges,
+    OverwritesFile,
+}
+
+impl Rationale {
+    pub const ALL: &[Rationale] = &[
+        Self::FormatsFilesystem,
+        Self::OverwritesBlockDevice,
+        Self::DeletesFromRoot,
+        Self::RawDiskWrite,
+        Self::ShredsFiles,
+        Self::DeletesRecursively,
+        Self::DiscardsUncommitted,
+        Self::DeletesUntracked,
+        Self::RewritesRemoteHistory,
+        Self::WorldWritable,
+        Self::FindDelete,
+        Self::DeletesFiles,
+        Self::RemovesDirectories,
+        Self::TruncatesFile,
+        Self::ChmodRecursive,
+        Self::ForceKills,
+        Self::KillsByName,
+        Self::DiscardsFileChanges,
+        Self::OverwritesFile,
+    ];
+
+    pub fn english(self) -> &'static str {
+        match self {
+            Self::FormatsFilesystem => "formats filesystem",
+            Self::OverwritesBlockDevice => "overwrites block device",
+            Self::DeletesFromRoot => "deletes from filesystem root",
+            Self::RawDiskWrite => "raw disk write",
+            Self::ShredsFiles => "overwrites file data; not recoverable",
+            Self::DeletesRecursively => "deletes files recursively; not recoverable",
+            Self::DiscardsUncommitted => "discards uncommitted changes",
+            Self::DeletesUntracked => "deletes untracked files",
+            Self::RewritesRemoteHistory => "rewrites remote history",
+            Self::WorldWritable => "makes files world-writable",
+            Self::FindDelete => "deletes files (find -delete)",
+            Self::DeletesFiles => "deletes files",
+            Self::RemovesDirectories => "removes directories",
+            Self::TruncatesFile => "truncates file",
+            Self::ChmodRecursive => "changes permissions recursively",
+            Self::ForceKills => "force-kills process",
+            Self::KillsByName => "kills processes by name",
+            Self::DiscardsFileChanges => "discards file changes",
+            Self::OverwritesFile => "overwrites file",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Risk {
+    pub severity: Severity,
+    pub reason: Rationale,
+    /// Short user-facing explanation, e.g. "deletes files recursively", in
+    /// English until localized.
+    pub rationale: String,
+}
+
+impl Risk {
+    fn new(severity: Severity, reason: Rationale) -> Self {
+        Self {
+            severity,
+            reason,
+            rationale: reason.english().to_string(),
+        }
+    }
+}
+
+/// Assess how destructive a command is. Returns the most severe match.
+///
+/// Uses simple substring matching — these are user-facing warnings, not security gates
+/// (the blocklist handles actual blocking). Simple checks are more robust and catch
+/// cases like `sudo rm` that position-anchored regexes would miss.
+pub fn assess_risk(command: &str) -> Option<Risk> {
+    use Rationale::*;
+    use Severity::*;
+    let patterns: &[(&str, Severity, Rationale)] = &[
+        ("mkfs", Critical, FormatsFilesystem),
+        ("> /dev/sd", Critical, OverwritesBlockDevice),
+        ("rm -rf / ", Critical, DeletesFromRoot),
+        ("rm -rf /*", Critical, DeletesFromRoot),
+        ("shred ", High, ShredsFiles),
+        ("rm -r", High, DeletesRecursively),
+        ("rm -fr", High, DeletesRecursively),
+        ("git reset --hard", High, DiscardsUncommitted),
+        ("git clean -f", High, DeletesUntracked),
+        ("git push --force", High, RewritesRemoteHistory),
+        ("git push -f", High, RewritesRemoteHistory),
+        ("chmod 777", High, WorldWritable),
+        ("-delete", High, FindDelete),
+        ("rm ", Medium, DeletesFiles),
+        ("rmdir ", Medium, RemovesDirectories),
+        ("truncate ", Medium, TruncatesFile),
+        ("chmod -R", Medium, ChmodRecursive),
+        ("kill -9", Medium, ForceKills),
+        ("pkill ", Medium, KillsByName),
+        ("git checkout -- ", Medium, DiscardsFileChanges),
+    ];
+
+    let mut worst = patterns
+        .iter()
+        .filter(|(pattern, _, _)| command.contains(pattern))
+        .max_by_key(|(_, severity, _)| *severity)
+        .map(|(_, severity, reason)| Risk::new(*severity, *reason));
+
+    // `dd` as a substring would also match `git add`.
+    if command.split_whitespace().any(|token| token == "dd") {
+        worst = Some(Risk::new(Critical, RawDiskWrite));
+    }
+
+    if command.trim_end().ends_with("rm -rf /") {
+        worst = Some(Risk::new(Critical, DeletesFromRoot));
+    }
+
+    if worst.is_none() {
+        if let Some(pos) = command.find("> ") {
+            if pos == 0 || command.as_bytes()[pos - 1] != b'>' {
+                worst = Some(Risk::new(Low, OverwritesFile));
+            }
+        }
+    }
+
+    worst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess_risk_picks_most_severe() {
+        let risk = assess_risk("sudo rm -rf /tmp/build").unwrap();
+        assert_eq!(risk.severity, Severity::High);
+        assert_eq!(risk.rationale, "deletes files recursively; not recoverable");
+
+        let risk = assess_risk("sudo dd if=/dev/zero of=/dev/sda").unwrap();
+        assert_eq!(risk.severity, Severity::Critical);
+        assert!(assess_risk("git add .").is_none());
+    }
+
+    #[test]
+    fn test_assess_risk_redirects() {
+        let risk = assess_risk("echo hi > out.txt").unwrap();
+        assert_eq!(risk.severity, Severity::Low);
+        assert!(assess_risk("echo hi >> out.txt").is_none());
+        assert!(assess_risk("git status").is_none());
+    }
+
+    #[test]
+    fn test_severity_names_round_trip() {
+        for severity in [
+            Severity::Low,
+            Severity::Medium,
+            Severity::High,
+            Severity::Critical,
+        ] {
+            assert_eq!(Severity::from_name(severity.name()), Some(severity));
+        }
+        assert_eq!(Severity::Critical.level(), 4);
+    }
+}
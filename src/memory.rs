@@ -0,0 +1,313 @@
+//! Entity memory: salient values (branches, containers, paths, hosts) pulled
+//! from executed commands and shared across terminals.
+//!
+//! The plugin reports each executed command via `synapse memory record` when
+//! `[memory] enabled = true`. Entities are kept newest-first in
+//! `~/.synapse/memory.json`, capped at `MAX_ENTITIES`, so a container started
+//! in one pane can be offered (through NL context or `synapse memory list`)
+//! in another.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of entities retained on disk.
+const MAX_ENTITIES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Branch,
+    Container,
+    Path,
+    Host,
+}
+
+impl EntityKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "branch" => Some(Self::Branch),
+            "container" => Some(Self::Container),
+            "path" => Some(Self::Path),
+            "host" => Some(Self::Host),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Branch => "branch",
+            Self::Container => "container",
+            Self::Path => "path",
+            Self::Host => "host",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entity {
+    pub kind: EntityKind,
+    pub value: String,
+    /// Unix timestamp (seconds) of the most recent sighting.
+    pub seen_at: u64,
+}
+
+pub fn memory_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("memory.json"))
+}
+
+/// Extract entities from `command` and merge them into the store at `path`.
+pub fn record(path: &Path, command: &str) -> io::Result<()> {
+    let extracted = extract_entities(command);
+    if extracted.is_empty() {
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let _lock = crate::records::lock_store(path)?;
+    let mut entities = load(path);
+    for (kind, value) in extracted.into_iter().rev() {
+        entities.retain(|e| !(e.kind == kind && e.value == value));
+        entities.insert(
+            0,
+            Entity {
+                kind,
+                value,
+                seen_at: now,
+            },
+        );
+    }
+    entities.truncate(MAX_ENTITIES);
+
+    // Write-then-rename so concurrent terminals never observe a torn file.
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string(&entities)?)?;
+    std::fs::rename(tmp, path)
+}
+
+/// Load entities, newest first. A missing or unreadable store is empty.
+pub fn load(path: &Path) -> Vec<Entity> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Most recent entities, optionally filtered by kind.
+pub fn recent(path: &Path, kind: Option<EntityKind>, limit: usize) -> Vec<Entity> {
+    load(path)
+        .into_iter()
+        .filter(|e| kind.is_none_or(|k| e.kind == k))
+        .take(limit)
+        .collect()
+}
+
+/// Pull salient entities out of a command line, in the order they appear.
+pub fn extract_entities(command: &str) -> Vec<(EntityKind, String)> {
+    let mut found = Vec::new();
+    for segment in command.split(['|', ';', '&']) {
+        let tokens: Vec<&str> = segment
+            .split_whitespace()
+            .map(|t| t.trim_matches(['\'', '"']))
+            .skip_while(|t| t.contains('=') || *t == "sudo" || *t == "command")
+            .collect();
+        extract_from_segment(&tokens, &mut found);
+    }
+
+    let mut deduped: Vec<(EntityKind, String)> = Vec::new();
+    for entity in found {
+        if !entity.1.is_empty() && !deduped.contains(&entity) {
+            deduped.push(entity);
+        }
+    }
+    deduped
+}
+
+fn extract_from_segment(tokens: &[&str], found: &mut Vec<(EntityKind, String)>) {
+    let Some((&program, args)) = tokens.split_first() else {
+        return;
+    };
+
+    match program {
+        "git" => {
+            let Some((&sub, rest)) = args.split_first() else {
+                return;
+            };
+            if matches!(
+                sub,
+                "checkout" | "switch" | "merge" | "rebase" | "branch" | "push" | "pull"
+            ) {
+                // `git checkout -- file` restores paths, not branches.
+                if rest.contains(&"--") {
+                    return;
+                }
+                let positional: Vec<&str> = positional_args(rest, &[]);
+                let branch = match sub {
+                    // `git push origin feature` -> second positional is the branch.
+                    "push" | "pull" => positional.get(1),
+                    _ => positional.first(),
+                };
+                if let Some(branch) = branch {
+                    found.push((EntityKind::Branch, branch.to_string()));
+                }
+            }
+        }
+        "docker" | "podman" => {
+            let Some((&sub, rest)) = args.split_first() else {
+                return;
+            };
+            match sub {
+                "logs" | "exec" | "stop" | "start" | "restart" | "rm" | "inspect" | "attach"
+                | "kill" => {
+                    const VALUE_FLAGS: &[&str] = &["-e", "-u", "-w", "--env", "--user", "--tail"];
+                    if let Some(id) = positional_args(rest, VALUE_FLAGS).first() {
+                        found.push((EntityKind::Container, id.to_string()));
+                    }
+                }
+                "run" => {
+                    if let Some(pos) = rest.iter().position(|t| *t == "--name") {
+                        if let Some(name) = rest.get(pos + 1) {
+                            found.push((EntityKind::Container, name.to_string()));
+                        }
+                    } else if let Some(name) = rest.iter().find_map(|t| t.strip_prefix("--name=")) {
+                        found.push((EntityKind::Container, name.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        "ssh" | "mosh" | "ping" | "telnet" => {
+            const VALUE_FLAGS: &[&str] = &[
+                "-p", "-i", "-l", "-o", "-F", "-J", "-L", "-R", "-D", "-c", "-W",
+            ];
+            if let Some(target) = positional_args(args, VALUE_FLAGS).first() {
+                found.push((EntityKind::Host, strip_user(target).to_string()));
+            }
+        }
+        "scp" | "rsync" => {
+            for arg in positional_args(args, &["-P", "-i", "-e"]) {
+                if let Some((host, path)) = arg.split_once(':') {
+                    if !host.is_empty() && !host.contains('/') {
+                        found.push((EntityKind::Host, strip_user(host).to_string()));
+                        if !path.is_empty() {
+                            found.push((EntityKind::Path, path.to_string()));
+                        }
+                    }
+                } else {
+                    found.push((EntityKind::Path, arg.to_string()));
+                }
+            }
+        }
+        "curl" | "wget" | "http" => {
+            for arg in args {
+                if let Some(host) = url_host(arg) {
+                    found.push((EntityKind::Host, host.to_string()));
+                }
+            }
+        }
+        "cd" | "vim" | "nvim" | "vi" | "code" | "less" | "cat" | "tail" | "head" | "open" => {
+            for arg in positional_args(args, &["-n"]) {
+                if arg != "-" && arg != "~" {
+                    found.push((EntityKind::Path, arg.to_string()));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Non-flag arguments, skipping the values of flags listed in `value_flags`.
+fn positional_args<'a>(args: &[&'a str], value_flags: &[&str]) -> Vec<&'a str> {
+    let mut positional = Vec::new();
+    let mut skip_next = false;
+    for &arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if arg.starts_with('-') {
+            skip_next = value_flags.contains(&arg);
+            continue;
+        }
+        positional.push(arg);
+    }
+    positional
+}
+
+fn strip_user(target: &str) -> &str {
+    target.rsplit_once('@').map_or(target, |(_, host)| host)
+}
+
+fn url_host(arg: &str) -> Option<&str> {
+    let (_, rest) = arg.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = strip_user(authority);
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_git_branches() {
+        assert_eq!(
+            extract_entities("git checkout -b feature/login"),
+            vec![(EntityKind::Branch, "feature/login".into())]
+        );
+        assert_eq!(
+            extract_entities("git push -u origin fix-123"),
+            vec![(EntityKind::Branch, "fix-123".into())]
+        );
+        assert!(extract_entities("git checkout -- src/main.rs").is_empty());
+    }
+
+    #[test]
+    fn test_extract_containers_and_hosts() {
+        assert_eq!(
+            extract_entities("docker logs -f --tail 50 web-1"),
+            vec![(EntityKind::Container, "web-1".into())]
+        );
+        assert_eq!(
+            extract_entities("ssh -p 2222 deploy@prod-db && curl https://api.example.com/v1"),
+            vec![
+                (EntityKind::Host, "prod-db".into()),
+                (EntityKind::Host, "api.example.com".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concurrent_records_are_all_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        std::thread::scope(|scope| {
+            for n in 0..8 {
+                let path = &path;
+                scope.spawn(move || record(path, &format!("git checkout branch-{n}")).unwrap());
+            }
+        });
+        assert_eq!(recent(&path, Some(EntityKind::Branch), 20).len(), 8);
+    }
+
+    #[test]
+    fn test_record_keeps_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        record(&path, "git checkout main").unwrap();
+        record(&path, "git checkout dev").unwrap();
+        record(&path, "git checkout main").unwrap();
+
+        let values: Vec<String> = recent(&path, Some(EntityKind::Branch), 10)
+            .into_iter()
+            .map(|e| e.value)
+            .collect();
+        assert_eq!(values, vec!["main", "dev"]);
+    }
+}
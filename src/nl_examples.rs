@@ -0,0 +1,154 @@
+//! Few-shot examples mined from accepted NL translations.
+//!
+//! When a command picked from the NL dropdown is executed and exits 0, the
+//! plugin records the `(query, command)` pair via `synapse examples record`.
+//! Translation then includes the most similar past pairs (TF-IDF cosine over
+//! query words) as few-shot examples in the prompt.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of examples retained on disk.
+const MAX_EXAMPLES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NlExample {
+    pub query: String,
+    pub command: String,
+    /// Unix timestamp (seconds) of the most recent successful run.
+    pub accepted_at: u64,
+}
+
+pub fn examples_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("nl_examples.json"))
+}
+
+/// Record a successfully executed translation, newest first.
+pub fn record(path: &Path, query: &str, command: &str) -> io::Result<()> {
+    let (query, command) = (query.trim(), command.trim());
+    if query.is_empty() || command.is_empty() {
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut examples = load(path);
+    examples.retain(|e| !(e.query == query && e.command == command));
+    examples.insert(
+        0,
+        NlExample {
+            query: query.to_string(),
+            command: command.to_string(),
+            accepted_at: now,
+        },
+    );
+    examples.truncate(MAX_EXAMPLES);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write-then-rename so concurrent terminals never observe a torn file.
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string(&examples)?)?;
+    std::fs::rename(tmp, path)
+}
+
+/// Load examples, newest first. A missing or unreadable store is empty.
+pub fn load(path: &Path) -> Vec<NlExample> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The `k` stored examples whose queries are most similar to `query`.
+/// Examples sharing no words with the query are never returned.
+pub fn most_similar(examples: &[NlExample], query: &str, k: usize) -> Vec<NlExample> {
+    let query_terms = terms(query);
+    if query_terms.is_empty() || k == 0 {
+        return Vec::new();
+    }
+
+    let docs: Vec<HashSet<String>> = examples.iter().map(|e| terms(&e.query)).collect();
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for doc in &docs {
+        for term in doc {
+            *doc_freq.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let idf = |term: &str| {
+        let df = doc_freq.get(term).copied().unwrap_or(0) as f64;
+        ((docs.len() as f64 + 1.0) / (df + 1.0)).ln() + 1.0
+    };
+    let norm = |doc: &HashSet<String>| doc.iter().map(|t| idf(t).powi(2)).sum::<f64>().sqrt();
+
+    let query_norm = norm(&query_terms);
+    let mut scored: Vec<(f64, usize)> = docs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, doc)| {
+            let dot: f64 = doc.intersection(&query_terms).map(|t| idf(t).powi(2)).sum();
+            (dot > 0.0).then(|| (dot / (norm(doc) * query_norm), i))
+        })
+        .collect();
+    // Stable sort keeps newer examples first among equal scores.
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    scored
+        .into_iter()
+        .take(k)
+        .map(|(_, i)| examples[i].clone())
+        .collect()
+}
+
+fn terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(query: &str, command: &str) -> NlExample {
+        NlExample {
+            query: query.into(),
+            command: command.into(),
+            accepted_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_most_similar_ranks_by_shared_rare_words() {
+        let examples = vec![
+            example("list all files", "ls -la"),
+            example("find large files", "find . -size +100M"),
+            example("show disk usage", "du -sh *"),
+        ];
+        let similar = most_similar(&examples, "find the large log files", 2);
+        assert_eq!(similar.len(), 2);
+        assert_eq!(similar[0].command, "find . -size +100M");
+        assert_eq!(similar[1].command, "ls -la");
+        assert!(most_similar(&examples, "restart nginx", 2).is_empty());
+    }
+
+    #[test]
+    fn test_record_dedupes_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nl_examples.json");
+        record(&path, "list files", "ls").unwrap();
+        record(&path, "disk usage", "du -sh").unwrap();
+        record(&path, "list files", "ls").unwrap();
+
+        let commands: Vec<String> = load(&path).into_iter().map(|e| e.command).collect();
+        assert_eq!(commands, vec!["ls", "du -sh"]);
+    }
+}
@@ -0,0 +1,124 @@
+//! Per-project pinned commands, kept in `.synapse.toml` at the project root:
+//!
+//! ```toml
+//! pins = ["make run-local", "docker compose up -d"]
+//! ```
+//!
+//! Typing the NL prefix alone (`?`) and Enter opens the pins in the dropdown,
+//! and translation lists them first among the project's commands.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+pub const PROJECT_FILE: &str = ".synapse.toml";
+
+/// The nearest `.synapse.toml` at or above `cwd`, stopping at the git root.
+pub fn find(cwd: &Path) -> Option<PathBuf> {
+    for dir in cwd.ancestors() {
+        let path = dir.join(PROJECT_FILE);
+        if path.is_file() {
+            return Some(path);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Pinned commands for the project containing `cwd`, in file order.
+pub fn load(cwd: &Path) -> Vec<String> {
+    find(cwd)
+        .and_then(|path| read_table(&path).ok())
+        .map(|table| pins_of(&table))
+        .unwrap_or_default()
+}
+
+/// Pin `command`, creating `.synapse.toml` at the project root (or `cwd`)
+/// if needed. Returns the file written, or `None` if already pinned.
+pub fn add(cwd: &Path, command: &str, scan_depth: usize) -> anyhow::Result<Option<PathBuf>> {
+    let path = find(cwd).unwrap_or_else(|| {
+        crate::project::find_project_root(cwd, scan_depth)
+            .unwrap_or_else(|| cwd.to_path_buf())
+            .join(PROJECT_FILE)
+    });
+    let mut table = if path.exists() {
+        read_table(&path)?
+    } else {
+        toml::Table::new()
+    };
+    let mut pins = pins_of(&table);
+    if pins.iter().any(|pin| pin == command) {
+        return Ok(None);
+    }
+    pins.push(command.to_string());
+    write_pins(&path, &mut table, pins)?;
+    Ok(Some(path))
+}
+
+/// Unpin `command`. Returns the file written, or `None` if it was not pinned.
+pub fn remove(cwd: &Path, command: &str) -> anyhow::Result<Option<PathBuf>> {
+    let Some(path) = find(cwd) else {
+        return Ok(None);
+    };
+    let mut table = read_table(&path)?;
+    let mut pins = pins_of(&table);
+    let before = pins.len();
+    pins.retain(|pin| pin != command);
+    if pins.len() == before {
+        return Ok(None);
+    }
+    write_pins(&path, &mut table, pins)?;
+    Ok(Some(path))
+}
+
+fn read_table(path: &Path) -> anyhow::Result<toml::Table> {
+    let content = std::fs::read_to_string(path)?;
+    toml::from_str(&content).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn pins_of(table: &toml::Table) -> Vec<String> {
+    table
+        .get("pins")
+        .and_then(toml::Value::as_array)
+        .map(|pins| {
+            pins.iter()
+                .filter_map(toml::Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn write_pins(path: &Path, table: &mut toml::Table, pins: Vec<String>) -> anyhow::Result<()> {
+    table.insert(
+        "pins".to_string(),
+        toml::Value::Array(pins.into_iter().map(toml::Value::String).collect()),
+    );
+    std::fs::write(path, toml::to_string(table)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_remove_pins_at_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let sub = dir.path().join("src");
+        std::fs::create_dir(&sub).unwrap();
+
+        let written = add(&sub, "make run-local", 3).unwrap().unwrap();
+        assert_eq!(written, dir.path().join(PROJECT_FILE));
+        assert!(add(&sub, "make run-local", 3).unwrap().is_none());
+        add(dir.path(), "docker compose up -d", 3).unwrap();
+        assert_eq!(load(&sub), vec!["make run-local", "docker compose up -d"]);
+
+        assert!(remove(&sub, "make run-local").unwrap().is_some());
+        assert!(remove(&sub, "make run-local").unwrap().is_none());
+        assert_eq!(load(&sub), vec!["docker compose up -d"]);
+    }
+}
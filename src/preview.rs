@@ -0,0 +1,183 @@
+//! Impact previews for file-mutating commands (`rm *.log` -> "37 files").
+//!
+//! Globs in the arguments of `rm`, `mv`, and `cp` are expanded against the
+//! working directory, only ever reading directory listings beneath it:
+//! absolute patterns, `~`, and `..` components are not previewed, symlinked
+//! directories are not entered, and the walk stops at [`MAX_MATCHES`].
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// Commands whose glob arguments are previewed.
+const FILE_COMMANDS: &[&str] = &["rm", "mv", "cp"];
+
+/// Expansion stops counting here; the preview then reads "N+ files".
+const MAX_MATCHES: usize = 10_000;
+
+/// Describe how many files each glob in `command` matches, e.g.
+/// `*.log → 37 files`. `None` when there is nothing to preview.
+pub fn preview(command: &str, cwd: &Path) -> Option<String> {
+    if command.contains(['|', ';', '&', '`', '$', '<', '>', '\'', '"', '\\']) {
+        return None;
+    }
+    let mut words = command.split_whitespace();
+    let program = words.next()?;
+    if !FILE_COMMANDS.contains(&program) {
+        return None;
+    }
+
+    let parts: Vec<String> = words
+        .filter(|word| !word.starts_with('-') && is_glob(word))
+        .filter_map(|pattern| {
+            let count = count_matches(pattern, cwd)?;
+            Some(match count {
+                0 => format!("{pattern} → no matches"),
+                1 => format!("{pattern} → 1 file"),
+                n if n >= MAX_MATCHES => format!("{pattern} → {MAX_MATCHES}+ files"),
+                n => format!("{pattern} → {n} files"),
+            })
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+fn is_glob(word: &str) -> bool {
+    word.contains(['*', '?', '['])
+}
+
+/// Count paths under `cwd` matching `pattern`, or `None` if the pattern
+/// could reach outside `cwd` or is not a glob zsh would expand the same way.
+fn count_matches(pattern: &str, cwd: &Path) -> Option<usize> {
+    if pattern.starts_with(['/', '~']) || pattern.contains("**") {
+        return None;
+    }
+    let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    if components.contains(&"..") {
+        return None;
+    }
+
+    let mut current = vec![cwd.to_path_buf()];
+    for (i, component) in components.iter().enumerate() {
+        let last = i + 1 == components.len();
+        let mut next = Vec::new();
+        if !is_glob(component) {
+            for dir in &current {
+                let path = dir.join(component);
+                if path.symlink_metadata().is_ok() {
+                    next.push(path);
+                }
+            }
+        } else {
+            let matcher = component_regex(component)?;
+            for dir in &current {
+                next.extend(matching_entries(dir, component, &matcher, last));
+                if next.len() >= MAX_MATCHES {
+                    return Some(MAX_MATCHES);
+                }
+            }
+        }
+        if !last {
+            // Only descend into real directories, never through symlinks.
+            next.retain(|p| p.symlink_metadata().is_ok_and(|m| m.is_dir()));
+        }
+        current = next;
+    }
+    Some(current.len().min(MAX_MATCHES))
+}
+
+fn matching_entries(dir: &Path, component: &str, matcher: &Regex, last: bool) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                return false;
+            };
+            // Like zsh, wildcards don't match a leading dot.
+            (!name.starts_with('.') || component.starts_with('.'))
+                && matcher.is_match(name)
+                && (last || entry.file_type().is_ok_and(|t| t.is_dir()))
+        })
+        .map(|entry| entry.path())
+        .take(MAX_MATCHES)
+        .collect()
+}
+
+/// Translate one path component of a zsh glob (`*`, `?`, `[...]`) to a regex.
+fn component_regex(component: &str) -> Option<Regex> {
+    let mut re = String::from("^");
+    let mut chars = component.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            '[' => {
+                re.push('[');
+                if matches!(chars.peek(), Some('!' | '^')) {
+                    chars.next();
+                    re.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                if !closed {
+                    return None;
+                }
+                re.push(']');
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_counts_glob_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.log", "b.log", ".hidden.log", "notes.txt"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        std::fs::create_dir_all(dir.path().join("logs/old")).unwrap();
+        std::fs::write(dir.path().join("logs/old/c.log"), "").unwrap();
+
+        assert_eq!(
+            preview("rm -f *.log", dir.path()).as_deref(),
+            Some("*.log → 2 files")
+        );
+        assert_eq!(
+            preview("rm logs/*/*.log *.tmp", dir.path()).as_deref(),
+            Some("logs/*/*.log → 1 file, *.tmp → no matches")
+        );
+        assert_eq!(
+            preview("cp [ab].log backup/", dir.path()).as_deref(),
+            Some("[ab].log → 2 files")
+        );
+    }
+
+    #[test]
+    fn test_preview_stays_inside_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(preview("rm ../*", dir.path()), None);
+        assert_eq!(preview("rm /tmp/*", dir.path()), None);
+        assert_eq!(preview("rm ~/*.log", dir.path()), None);
+        assert_eq!(preview("ls *.log", dir.path()), None);
+        assert_eq!(preview("rm notes.txt", dir.path()), None);
+    }
+}
@@ -4,18 +4,19 @@ pub(crate) fn has_any_file(root: &Path, candidates: &[&str]) -> bool {
     candidates.iter().any(|name| root.join(name).exists())
 }
 
+/// Walk up from `cwd` to the nearest directory containing `.git`.
+pub fn find_git_root(cwd: &Path) -> Option<PathBuf> {
+    cwd.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
 /// Walk up from `cwd` to find the project root.
 /// First tries an unbounded walk to find a `.git` directory.
 /// If none found, walks up `max_depth` levels looking for project files.
 pub fn find_project_root(cwd: &Path, max_depth: usize) -> Option<PathBuf> {
-    let mut current = cwd.to_path_buf();
-    loop {
-        if current.join(".git").exists() {
-            return Some(current);
-        }
-        if !current.pop() {
-            break;
-        }
+    if let Some(root) = find_git_root(cwd) {
+        return Some(root);
     }
 
     let mut current = cwd.to_path_buf();
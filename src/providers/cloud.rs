@@ -0,0 +1,304 @@
+//! Cloud profiles and resources: AWS profiles, regions, and S3 buckets, GCP
+//! projects, and Kubernetes contexts.
+//!
+//! AWS profiles are parsed from `~/.aws/config` and `~/.aws/credentials`, and
+//! kube contexts come from the local kubeconfig; neither touches the network.
+//! Regions, buckets, and projects need API calls, so they are only listed
+//! when `spec.cloud_network_completions` is enabled and synapse is not
+//! offline. Command-backed listings are memoized under `~/.synapse/cloud/`,
+//! one file per account selection (`AWS_PROFILE`, the active gcloud
+//! configuration and project, `KUBECONFIG`), so switching account never
+//! serves the previous account's resources.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudListing {
+    AwsProfiles,
+    AwsRegions,
+    S3Buckets,
+    GcpProjects,
+    KubeContexts,
+}
+
+impl CloudListing {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "aws-profiles" => Some(Self::AwsProfiles),
+            "aws-regions" => Some(Self::AwsRegions),
+            "s3-buckets" => Some(Self::S3Buckets),
+            "gcp-projects" => Some(Self::GcpProjects),
+            "kube-contexts" => Some(Self::KubeContexts),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AwsProfiles => "aws-profiles",
+            Self::AwsRegions => "aws-regions",
+            Self::S3Buckets => "s3-buckets",
+            Self::GcpProjects => "gcp-projects",
+            Self::KubeContexts => "kube-contexts",
+        }
+    }
+
+    /// Whether listing requires calling a cloud API.
+    pub fn needs_network(self) -> bool {
+        matches!(self, Self::AwsRegions | Self::S3Buckets | Self::GcpProjects)
+    }
+
+    /// Environment variables that select the account or config a listing
+    /// comes from.
+    fn scope_vars(self) -> &'static [&'static str] {
+        match self {
+            Self::AwsRegions | Self::S3Buckets => &[
+                "AWS_PROFILE",
+                "AWS_DEFAULT_PROFILE",
+                "AWS_ACCESS_KEY_ID",
+                "AWS_CONFIG_FILE",
+                "AWS_SHARED_CREDENTIALS_FILE",
+            ],
+            Self::GcpProjects => &[
+                "CLOUDSDK_CONFIG",
+                "CLOUDSDK_ACTIVE_CONFIG_NAME",
+                "CLOUDSDK_CORE_ACCOUNT",
+                "CLOUDSDK_CORE_PROJECT",
+            ],
+            Self::KubeContexts => &["KUBECONFIG"],
+            Self::AwsProfiles => &[],
+        }
+    }
+
+    /// How long a memoized listing stays fresh. Network listings are kept
+    /// conservatively long since each refresh is a slow, billed API call.
+    fn listing_ttl(self) -> Duration {
+        match self {
+            Self::AwsRegions => Duration::from_secs(7 * 86400),
+            Self::S3Buckets | Self::GcpProjects => Duration::from_secs(3600),
+            Self::KubeContexts => Duration::from_secs(60),
+            Self::AwsProfiles => Duration::ZERO,
+        }
+    }
+}
+
+/// Return the sorted, deduplicated names for `listing`. Network listings are
+/// empty unless `allow_network` is set. With `refresh`, the memoized listing
+/// is ignored and rebuilt.
+pub fn list(listing: CloudListing, allow_network: bool, refresh: bool) -> Vec<String> {
+    if listing.needs_network() && !allow_network {
+        return Vec::new();
+    }
+    if listing == CloudListing::AwsProfiles {
+        return aws_profiles();
+    }
+
+    let memo = listing_path(listing);
+    if !refresh {
+        if let Some(names) = memo
+            .as_deref()
+            .and_then(|path| super::read_fresh_listing(path, listing.listing_ttl()))
+        {
+            return names;
+        }
+    }
+
+    let output = match listing {
+        CloudListing::AwsRegions => run_listing(
+            "aws",
+            &[
+                "ec2",
+                "describe-regions",
+                "--query",
+                "Regions[].RegionName",
+                "--output",
+                "text",
+            ],
+        ),
+        CloudListing::S3Buckets => run_listing(
+            "aws",
+            &[
+                "s3api",
+                "list-buckets",
+                "--query",
+                "Buckets[].Name",
+                "--output",
+                "text",
+            ],
+        ),
+        CloudListing::GcpProjects => run_listing(
+            "gcloud",
+            &["projects", "list", "--format=value(projectId)", "--quiet"],
+        ),
+        CloudListing::KubeContexts => {
+            run_listing("kubectl", &["config", "get-contexts", "-o", "name"])
+        }
+        CloudListing::AwsProfiles => unreachable!("parsed from local files above"),
+    };
+    // A failed command (missing tool, expired credentials) is not memoized.
+    let Some(output) = output else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = output.split_whitespace().map(str::to_string).collect();
+    names.sort();
+    names.dedup();
+    if let Some(path) = memo {
+        super::write_listing(&path, &names);
+    }
+    names
+}
+
+fn listing_path(listing: CloudListing) -> Option<PathBuf> {
+    let mut scope = account_scope(listing, |var| std::env::var(var).ok());
+    if listing == CloudListing::GcpProjects {
+        // `gcloud config configurations activate` switches account without
+        // touching the environment.
+        scope.push_str(&gcloud_active_config().unwrap_or_default());
+    }
+    let key = super::cache_key(scope.as_bytes());
+    dirs::home_dir().map(|h| {
+        h.join(".synapse")
+            .join("cloud")
+            .join(format!("{}-{key}.txt", listing.name()))
+    })
+}
+
+/// `VAR=value` lines for the variables selecting `listing`'s account.
+fn account_scope(listing: CloudListing, var: impl Fn(&str) -> Option<String>) -> String {
+    listing
+        .scope_vars()
+        .iter()
+        .map(|name| format!("{name}={}\n", var(name).unwrap_or_default()))
+        .collect()
+}
+
+fn gcloud_active_config() -> Option<String> {
+    let dir = match std::env::var("CLOUDSDK_CONFIG") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::home_dir()?.join(".config").join("gcloud"),
+    };
+    let name = std::fs::read_to_string(dir.join("active_config")).ok()?;
+    let name = name.trim();
+    let config = std::fs::read_to_string(dir.join("configurations").join(format!("config_{name}")))
+        .unwrap_or_default();
+    Some(format!("{name}\n{config}"))
+}
+
+/// Run a read-only listing command, returning its stdout on success.
+fn run_listing(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .env("AWS_PAGER", "")
+        .env("CLOUDSDK_CORE_DISABLE_PROMPTS", "1")
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// --- aws ---
+
+fn aws_file(env_var: &str, default_name: &str) -> Option<PathBuf> {
+    if let Ok(path) = std::env::var(env_var) {
+        if !path.is_empty() {
+            return Some(PathBuf::from(path));
+        }
+    }
+    dirs::home_dir().map(|h| h.join(".aws").join(default_name))
+}
+
+fn aws_profiles() -> Vec<String> {
+    let read = |path: Option<PathBuf>| {
+        path.and_then(|p| std::fs::read_to_string(p).ok())
+            .unwrap_or_default()
+    };
+    let mut names = parse_aws_profiles(
+        &read(aws_file("AWS_CONFIG_FILE", "config")),
+        &read(aws_file("AWS_SHARED_CREDENTIALS_FILE", "credentials")),
+    );
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Profile names from the shared config (`[profile name]`, plus `[default]`)
+/// and credentials (`[name]`) files. Other config sections such as
+/// `[sso-session ...]` and `[services ...]` are not profiles.
+fn parse_aws_profiles(config: &str, credentials: &str) -> Vec<String> {
+    let sections = |content: &str| -> Vec<String> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+                Some(inner.trim().to_string())
+            })
+            .collect()
+    };
+
+    let from_config = sections(config).into_iter().filter_map(|section| {
+        if section == "default" {
+            return Some(section);
+        }
+        section
+            .strip_prefix("profile ")
+            .map(|name| name.trim().to_string())
+    });
+    from_config
+        .chain(sections(credentials))
+        .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aws_profiles() {
+        let config = "\
+[default]
+region = us-east-1
+
+[profile staging]
+sso_session = corp
+
+[sso-session corp]
+sso_region = us-east-1
+";
+        let credentials = "[default]\naws_access_key_id = x\n\n[ci]\n";
+        assert_eq!(
+            parse_aws_profiles(config, credentials),
+            vec!["default", "staging", "default", "ci"]
+        );
+    }
+
+    #[test]
+    fn test_network_listings_require_opt_in() {
+        assert!(list(CloudListing::S3Buckets, false, false).is_empty());
+        assert!(list(CloudListing::GcpProjects, false, true).is_empty());
+    }
+
+    #[test]
+    fn test_account_scope_follows_selected_account() {
+        let scope = |listing, profile: &str, project: &str| {
+            let (profile, project) = (profile.to_string(), project.to_string());
+            account_scope(listing, |var| match var {
+                "AWS_PROFILE" => Some(profile.clone()),
+                "CLOUDSDK_CORE_PROJECT" => Some(project.clone()),
+                _ => None,
+            })
+        };
+        let buckets = CloudListing::S3Buckets;
+        assert_ne!(scope(buckets, "prod", "a"), scope(buckets, "dev", "a"));
+        assert_eq!(scope(buckets, "prod", "a"), scope(buckets, "prod", "b"));
+        let projects = CloudListing::GcpProjects;
+        assert_ne!(scope(projects, "prod", "a"), scope(projects, "prod", "b"));
+    }
+}
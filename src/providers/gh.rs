@@ -0,0 +1,176 @@
+//! Open pull requests and issues of the current GitHub repository, listed via
+//! the `gh` CLI.
+//!
+//! Unlike the package registries these listings come from the network, so
+//! they are memoized per repository under `~/.synapse/gh/` with a short TTL.
+//! In offline mode `gh` is never run and the last listing is reused as-is.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+/// How long a memoized listing stays fresh.
+const LISTING_TTL: Duration = Duration::from_secs(300);
+/// Maximum number of items requested from `gh`.
+const LIST_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhListing {
+    /// Open pull requests by number.
+    Prs,
+    /// Open pull requests by head branch name.
+    PrBranches,
+    /// Open issues by number.
+    Issues,
+}
+
+impl GhListing {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "prs" => Some(Self::Prs),
+            "pr-branches" => Some(Self::PrBranches),
+            "issues" => Some(Self::Issues),
+            _ => None,
+        }
+    }
+
+    /// Both PR listings share one `gh pr list` call and cache file.
+    fn cache_name(self) -> &'static str {
+        match self {
+            Self::Prs | Self::PrBranches => "prs",
+            Self::Issues => "issues",
+        }
+    }
+}
+
+/// One open pull request or issue as returned by `gh ... list --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GhItem {
+    pub number: u64,
+    pub title: String,
+    #[serde(
+        default,
+        rename = "headRefName",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub head_ref_name: Option<String>,
+}
+
+/// Return `(value, title)` pairs for the repository containing `cwd`.
+/// With `refresh`, the memoized listing is ignored and rebuilt. Returns an
+/// empty list outside a git repository or when `gh` fails.
+pub fn list(listing: GhListing, cwd: &Path, refresh: bool, offline: bool) -> Vec<(String, String)> {
+    let Some(repo) = crate::project::find_git_root(cwd) else {
+        return Vec::new();
+    };
+    let cache = cache_path(&repo, listing);
+
+    // Offline, any previous listing beats none; otherwise honor the TTL.
+    let ttl = if offline { Duration::MAX } else { LISTING_TTL };
+    let cached = if refresh && !offline {
+        None
+    } else {
+        cache
+            .as_deref()
+            .and_then(|path| read_fresh_listing(path, ttl))
+    };
+    let items = match cached {
+        Some(items) => items,
+        None if offline => Vec::new(),
+        None => {
+            let Some(items) = run_gh_list(listing, &repo) else {
+                return Vec::new();
+            };
+            if let Some(path) = cache {
+                if let Some(parent) = path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Ok(json) = serde_json::to_string(&items) {
+                    let _ = std::fs::write(path, json);
+                }
+            }
+            items
+        }
+    };
+
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let value = match listing {
+                GhListing::Prs | GhListing::Issues => item.number.to_string(),
+                GhListing::PrBranches => item.head_ref_name?,
+            };
+            Some((value, item.title))
+        })
+        .collect()
+}
+
+/// Cache file for one repository, keyed by a hash of its root path.
+fn cache_path(repo: &Path, listing: GhListing) -> Option<PathBuf> {
+    let key = super::cache_key(repo.as_os_str().as_encoded_bytes());
+    dirs::home_dir().map(|h| {
+        h.join(".synapse")
+            .join("gh")
+            .join(format!("{key}-{}.json", listing.cache_name()))
+    })
+}
+
+fn read_fresh_listing(path: &Path, ttl: Duration) -> Option<Vec<GhItem>> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    if age > ttl {
+        return None;
+    }
+    parse_items(&std::fs::read_to_string(path).ok()?)
+}
+
+fn parse_items(json: &str) -> Option<Vec<GhItem>> {
+    serde_json::from_str(json).ok()
+}
+
+/// Run `gh pr list` / `gh issue list` in `repo`. Prompts are disabled so a
+/// missing login fails fast instead of blocking completion.
+fn run_gh_list(listing: GhListing, repo: &Path) -> Option<Vec<GhItem>> {
+    let (kind, fields) = match listing {
+        GhListing::Prs | GhListing::PrBranches => ("pr", "number,title,headRefName"),
+        GhListing::Issues => ("issue", "number,title"),
+    };
+    let limit = LIST_LIMIT.to_string();
+    let output = std::process::Command::new("gh")
+        .args([kind, "list", "--state", "open", "--limit", &limit])
+        .args(["--json", fields])
+        .current_dir(repo)
+        .env("GH_PROMPT_DISABLED", "1")
+        .env("GH_NO_UPDATE_NOTIFIER", "1")
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_items(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_items() {
+        let json = r#"[
+            {"number": 123, "title": "Fix login bug", "headRefName": "fix-login"},
+            {"number": 7, "title": "Add docs"}
+        ]"#;
+        let items = parse_items(json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].number, 123);
+        assert_eq!(items[0].title, "Fix login bug");
+        assert_eq!(items[0].head_ref_name.as_deref(), Some("fix-login"));
+        assert_eq!(items[1].head_ref_name, None);
+        assert!(parse_items("not json").is_none());
+    }
+}
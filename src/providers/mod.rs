@@ -0,0 +1,60 @@
+//! Completion-time value sources backed by local caches.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+pub mod cloud;
+pub mod gh;
+pub mod registry;
+pub mod terraform;
+
+/// Read a newline-separated listing memoized at `path`, unless it is older
+/// than `ttl`.
+fn read_fresh_listing(path: &Path, ttl: Duration) -> Option<Vec<String>> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    if age > ttl {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(
+        content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Memoize a listing at `path`, one entry per line. Failures are ignored.
+fn write_listing(path: &Path, names: &[String]) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, names.join("\n"));
+}
+
+/// Short hex digest naming a cache file. Unlike `DefaultHasher`, SHA-256 is
+/// stable across Rust releases, so upgrading does not orphan caches.
+fn cache_key(input: &[u8]) -> String {
+    Sha256::digest(input)
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_is_stable() {
+        assert_eq!(cache_key(b"/home/me/repo"), cache_key(b"/home/me/repo"));
+        assert_eq!(cache_key(b"abc"), "ba7816bf8f01cfea");
+    }
+}
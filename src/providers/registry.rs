@@ -0,0 +1,321 @@
+//! Package names from local package-manager caches (npm, cargo, pip, brew).
+//!
+//! Nothing here touches the network: names come from the caches the package
+//! managers already keep on disk (or, for Homebrew, its local tap checkout),
+//! so only packages the user has fetched before are offered. Listings are
+//! memoized under `~/.synapse/registry/` because walking a large npm cache or
+//! running `brew formulae` on every TAB would be too slow.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Upper bound on cache files inspected per scan.
+const MAX_INDEX_FILES: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Npm,
+    Cargo,
+    Pip,
+    /// All formulae and casks known to the local Homebrew taps.
+    Brew,
+    /// Formulae and casks currently installed by Homebrew.
+    BrewInstalled,
+}
+
+impl Ecosystem {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "npm" => Some(Self::Npm),
+            "cargo" => Some(Self::Cargo),
+            "pip" => Some(Self::Pip),
+            "brew" => Some(Self::Brew),
+            "brew-installed" => Some(Self::BrewInstalled),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Cargo => "cargo",
+            Self::Pip => "pip",
+            Self::Brew => "brew",
+            Self::BrewInstalled => "brew-installed",
+        }
+    }
+
+    /// How long a memoized listing stays fresh. Tap contents change rarely
+    /// and the plugin refreshes Homebrew listings after `brew` mutations.
+    fn listing_ttl(self) -> Duration {
+        match self {
+            Self::Brew | Self::BrewInstalled => Duration::from_secs(86400),
+            Self::Npm | Self::Cargo | Self::Pip => Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Return the sorted, deduplicated package names found in the local cache.
+/// With `refresh`, the memoized listing is ignored and rebuilt.
+pub fn cached_packages(ecosystem: Ecosystem, refresh: bool) -> Vec<String> {
+    let listing = listing_path(ecosystem);
+    if !refresh {
+        if let Some(names) = listing
+            .as_deref()
+            .and_then(|path| super::read_fresh_listing(path, ecosystem.listing_ttl()))
+        {
+            return names;
+        }
+    }
+
+    let mut names = match ecosystem {
+        Ecosystem::Npm => scan_npm_cache(),
+        Ecosystem::Cargo => scan_cargo_index(),
+        Ecosystem::Pip => scan_pip_cache(),
+        Ecosystem::Brew => run_brew(&["formulae"])
+            .into_iter()
+            .chain(run_brew(&["casks"]))
+            .collect(),
+        Ecosystem::BrewInstalled => run_brew(&["list", "-1"]),
+    };
+    names.sort();
+    names.dedup();
+
+    if let Some(path) = listing {
+        super::write_listing(&path, &names);
+    }
+    names
+}
+
+fn listing_path(ecosystem: Ecosystem) -> Option<PathBuf> {
+    dirs::home_dir().map(|h| {
+        h.join(".synapse")
+            .join("registry")
+            .join(format!("{}.txt", ecosystem.name()))
+    })
+}
+
+/// Depth-first walk over regular files under `root`, visiting at most
+/// `MAX_INDEX_FILES` entries.
+fn walk_files(root: &Path, mut visit: impl FnMut(&Path)) {
+    let mut stack = vec![root.to_path_buf()];
+    let mut visited = 0;
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(entry.path());
+            } else if file_type.is_file() {
+                visit(&entry.path());
+                visited += 1;
+                if visited >= MAX_INDEX_FILES {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// --- npm ---
+
+fn npm_cache_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("npm_config_cache") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::home_dir().map(|h| h.join(".npm"))
+}
+
+fn scan_npm_cache() -> Vec<String> {
+    let Some(root) = npm_cache_dir() else {
+        return Vec::new();
+    };
+    let mut names = Vec::new();
+    walk_files(&root.join("_cacache").join("index-v5"), |path| {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return;
+        };
+        for line in content.lines() {
+            let Some((_, json)) = line.split_once('\t') else {
+                continue;
+            };
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(json) else {
+                continue;
+            };
+            if let Some(name) = entry["key"].as_str().and_then(npm_package_from_key) {
+                names.push(name);
+            }
+        }
+    });
+    names
+}
+
+/// Extract a package name from an npm cacache key such as
+/// `make-fetch-happen:request-cache:https://registry.npmjs.org/@scope%2fpkg`.
+/// Tarball keys (`.../pkg/-/pkg-1.0.0.tgz`) are ignored.
+fn npm_package_from_key(key: &str) -> Option<String> {
+    let url = key.strip_prefix("make-fetch-happen:request-cache:")?;
+    let (_, rest) = url.split_once("://")?;
+    let (host, path) = rest.split_once('/')?;
+    if !host.contains("registry") || path.is_empty() || path.contains("/-/") {
+        return None;
+    }
+    let name = path
+        .replace("%2f", "/")
+        .replace("%2F", "/")
+        .replace("%40", "@");
+    (!name.starts_with('-')).then_some(name)
+}
+
+// --- cargo ---
+
+fn cargo_home() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("CARGO_HOME") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::home_dir().map(|h| h.join(".cargo"))
+}
+
+/// The sparse index cache stores one file per crate, named after the crate.
+fn scan_cargo_index() -> Vec<String> {
+    let Some(home) = cargo_home() else {
+        return Vec::new();
+    };
+    let Ok(registries) = std::fs::read_dir(home.join("registry").join("index")) else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    for registry in registries.flatten() {
+        walk_files(&registry.path().join(".cache"), |path| {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                return;
+            };
+            if !name.starts_with('.') && name != "config.json" {
+                names.push(name.to_string());
+            }
+        });
+    }
+    names
+}
+
+// --- pip ---
+
+fn pip_cache_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("PIP_CACHE_DIR") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::cache_dir().map(|d| d.join("pip"))
+}
+
+fn scan_pip_cache() -> Vec<String> {
+    let Some(root) = pip_cache_dir() else {
+        return Vec::new();
+    };
+    let mut names = Vec::new();
+    walk_files(&root.join("wheels"), |path| {
+        if let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(pip_package_from_wheel)
+        {
+            names.push(name);
+        }
+    });
+    names
+}
+
+/// Extract the normalized project name from a wheel filename
+/// (`Foo_Bar-1.0-py3-none-any.whl` -> `foo-bar`).
+fn pip_package_from_wheel(filename: &str) -> Option<String> {
+    let stem = filename.strip_suffix(".whl")?;
+    let name = stem.split('-').next()?;
+    (!name.is_empty()).then(|| name.to_ascii_lowercase().replace(['_', '.'], "-"))
+}
+
+// --- brew ---
+
+/// Run a read-only `brew` listing command with auto-update disabled.
+fn run_brew(args: &[&str]) -> Vec<String> {
+    let output = match std::process::Command::new("brew")
+        .args(args)
+        .env("HOMEBREW_NO_AUTO_UPDATE", "1")
+        .env("HOMEBREW_NO_ANALYTICS", "1")
+        .env("NO_COLOR", "1")
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    parse_brew_listing(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Names from `brew formulae`/`casks`/`list -1` output: one per line. Tap
+/// formulae keep their `user/tap/` prefix, which `brew install` accepts.
+fn parse_brew_listing(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("==>"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npm_package_from_key() {
+        assert_eq!(
+            npm_package_from_key(
+                "make-fetch-happen:request-cache:https://registry.npmjs.org/lodash"
+            ),
+            Some("lodash".into())
+        );
+        assert_eq!(
+            npm_package_from_key(
+                "make-fetch-happen:request-cache:https://registry.npmjs.org/@types%2fnode"
+            ),
+            Some("@types/node".into())
+        );
+        assert_eq!(
+            npm_package_from_key(
+                "make-fetch-happen:request-cache:https://registry.npmjs.org/lodash/-/lodash-4.17.21.tgz"
+            ),
+            None
+        );
+        assert_eq!(npm_package_from_key("pacote:tarball:lodash@4"), None);
+    }
+
+    #[test]
+    fn test_parse_brew_listing() {
+        let stdout = "==> Formulae\nwget\n  jq  \n\nhashicorp/tap/terraform\n";
+        assert_eq!(
+            parse_brew_listing(stdout),
+            vec!["wget", "jq", "hashicorp/tap/terraform"]
+        );
+    }
+
+    #[test]
+    fn test_pip_package_from_wheel() {
+        assert_eq!(
+            pip_package_from_wheel("Foo_Bar-1.0-py3-none-any.whl"),
+            Some("foo-bar".into())
+        );
+        assert_eq!(pip_package_from_wheel("README.txt"), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Terraform resource addresses from local state and JSON plan files.
+//!
+//! Addresses come from the current workspace's local state
+//! (`terraform.tfstate`, or `terraform.tfstate.d/<workspace>/terraform.tfstate`)
+//! and from any `*.tfplan.json` written by `terraform show -json`. Remote
+//! backends are not queried; running `terraform state list` on every TAB would
+//! be far too slow.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Return the sorted, deduplicated resource addresses visible from `cwd`.
+pub fn resource_addresses(cwd: &Path) -> Vec<String> {
+    let mut addresses = Vec::new();
+
+    if let Ok(content) = std::fs::read_to_string(state_path(cwd)) {
+        addresses.extend(parse_state_addresses(&content));
+    }
+    if let Ok(entries) = std::fs::read_dir(cwd) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_plan = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(".tfplan.json"));
+            if !is_plan {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(&path) {
+                addresses.extend(parse_plan_addresses(&content));
+            }
+        }
+    }
+
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+/// The selected workspace: `TF_WORKSPACE`, then `.terraform/environment`.
+fn current_workspace(cwd: &Path) -> String {
+    std::env::var("TF_WORKSPACE")
+        .ok()
+        .filter(|ws| !ws.is_empty())
+        .or_else(|| {
+            std::fs::read_to_string(cwd.join(".terraform").join("environment"))
+                .ok()
+                .map(|ws| ws.trim().to_string())
+                .filter(|ws| !ws.is_empty())
+        })
+        .unwrap_or_else(|| "default".to_string())
+}
+
+fn state_path(cwd: &Path) -> PathBuf {
+    let workspace = current_workspace(cwd);
+    if workspace == "default" {
+        cwd.join("terraform.tfstate")
+    } else {
+        cwd.join("terraform.tfstate.d")
+            .join(workspace)
+            .join("terraform.tfstate")
+    }
+}
+
+/// Addresses of every resource instance in a v4 state file, e.g.
+/// `module.net.aws_subnet.private["a"]` or `data.aws_ami.ubuntu`.
+fn parse_state_addresses(json: &str) -> Vec<String> {
+    let Ok(state) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+    let Some(resources) = state["resources"].as_array() else {
+        return Vec::new();
+    };
+
+    let mut addresses = Vec::new();
+    for resource in resources {
+        let (Some(kind), Some(name)) = (resource["type"].as_str(), resource["name"].as_str())
+        else {
+            continue;
+        };
+        let mut base = String::new();
+        if let Some(module) = resource["module"].as_str() {
+            base.push_str(module);
+            base.push('.');
+        }
+        if resource["mode"].as_str() == Some("data") {
+            base.push_str("data.");
+        }
+        base.push_str(&format!("{kind}.{name}"));
+
+        let instances = resource["instances"].as_array().map(Vec::as_slice);
+        let keys: Vec<&Value> = instances
+            .unwrap_or_default()
+            .iter()
+            .map(|instance| &instance["index_key"])
+            .filter(|key| !key.is_null())
+            .collect();
+        if keys.is_empty() {
+            addresses.push(base);
+            continue;
+        }
+        for key in keys {
+            match key {
+                Value::Number(n) => addresses.push(format!("{base}[{n}]")),
+                Value::String(s) => addresses.push(format!("{base}[\"{s}\"]")),
+                _ => {}
+            }
+        }
+    }
+    addresses
+}
+
+/// Addresses of the resource changes in `terraform show -json <plan>` output.
+fn parse_plan_addresses(json: &str) -> Vec<String> {
+    let Ok(plan) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+    plan["resource_changes"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|change| change["address"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state_addresses() {
+        let state = r#"{
+            "version": 4,
+            "resources": [
+                {"mode": "managed", "type": "aws_instance", "name": "web",
+                 "instances": [{"attributes": {}}]},
+                {"module": "module.net", "mode": "managed", "type": "aws_subnet", "name": "private",
+                 "instances": [{"index_key": "a"}, {"index_key": "b"}]},
+                {"mode": "data", "type": "aws_ami", "name": "ubuntu", "instances": []},
+                {"mode": "managed", "type": "null_resource", "name": "n",
+                 "instances": [{"index_key": 0}]}
+            ]
+        }"#;
+        assert_eq!(
+            parse_state_addresses(state),
+            vec![
+                "aws_instance.web",
+                r#"module.net.aws_subnet.private["a"]"#,
+                r#"module.net.aws_subnet.private["b"]"#,
+                "data.aws_ami.ubuntu",
+                "null_resource.n[0]",
+            ]
+        );
+        assert!(parse_state_addresses("not json").is_empty());
+    }
+
+    #[test]
+    fn test_resource_addresses_reads_workspace_state_and_plans() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".terraform")).unwrap();
+        std::fs::write(dir.path().join(".terraform/environment"), "staging").unwrap();
+        let ws_dir = dir.path().join("terraform.tfstate.d/staging");
+        std::fs::create_dir_all(&ws_dir).unwrap();
+        std::fs::write(
+            ws_dir.join("terraform.tfstate"),
+            r#"{"resources": [{"mode": "managed", "type": "aws_s3_bucket", "name": "logs", "instances": [{}]}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("next.tfplan.json"),
+            r#"{"resource_changes": [{"address": "aws_iam_role.ci"}, {"address": "aws_s3_bucket.logs"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resource_addresses(dir.path()),
+            vec!["aws_iam_role.ci", "aws_s3_bucket.logs"]
+        );
+    }
+}
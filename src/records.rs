@@ -0,0 +1,333 @@
+//! Command lines synapse keeps on disk, for export and selective purge.
+//!
+//! Several stores under `~/.synapse/` hold commands the user ran or was
+//! offered: few-shot examples, command durations, cached explanations,
+//! entity memory, flag values, the policy audit log, and the LLM audit log.
+//! `synapse history export` dumps them as JSON lines and `synapse history
+//! forget` removes matching entries, so a command that leaked a secret or a
+//! sensitive hostname can be purged everywhere at once.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// A JSON store: an array of objects, newest first.
+struct JsonStore {
+    name: &'static str,
+    file: &'static str,
+    /// Fields matched against `--matching`.
+    text_fields: &'static [&'static str],
+    /// Unix-seconds field compared against `--before`, if the store has one.
+    time_field: Option<&'static str>,
+}
+
+const JSON_STORES: &[JsonStore] = &[
+    JsonStore {
+        name: "nl_examples",
+        file: "nl_examples.json",
+        text_fields: &["query", "command"],
+        time_field: Some("accepted_at"),
+    },
+    JsonStore {
+        name: "durations",
+        file: "durations.json",
+        text_fields: &["command"],
+        time_field: Some("ran_at"),
+    },
+    JsonStore {
+        name: "explain_cache",
+        file: "explain_cache.json",
+        text_fields: &["command", "summary"],
+        time_field: None,
+    },
+    JsonStore {
+        name: "flag_values",
+        file: "flag_values.json",
+        text_fields: &["command", "flag", "value"],
+        time_field: Some("seen_at"),
+    },
+    JsonStore {
+        name: "memory",
+        file: "memory.json",
+        text_fields: &["value"],
+        time_field: Some("seen_at"),
+    },
+];
+
+/// Tab-separated `time source cwd rule command` lines (see
+/// [`crate::security::record_blocked`]), current file then rotated.
+const AUDIT_LOGS: &[&str] = &["policy-audit.log", "policy-audit.log.1"];
+
+/// JSON lines written by [`crate::llm::audit`], current file then rotated.
+const LLM_AUDIT_LOGS: &[&str] = &["llm-audit.jsonl", "llm-audit.jsonl.1"];
+
+/// Which entries `forget` removes. An entry must satisfy every filter given;
+/// entries without a timestamp (the explain cache) pass any `before` filter.
+pub struct Filter {
+    pub matching: Option<Regex>,
+    /// Unix seconds; only entries recorded earlier match.
+    pub before: Option<u64>,
+}
+
+impl Filter {
+    fn matches(&self, texts: &[&str], at: Option<u64>) -> bool {
+        let text_ok = self
+            .matching
+            .as_ref()
+            .is_none_or(|re| texts.iter().any(|t| re.is_match(t)));
+        let time_ok = match (self.before, at) {
+            (Some(before), Some(at)) => at < before,
+            _ => true,
+        };
+        text_ok && time_ok
+    }
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse"))
+}
+
+/// Every stored entry as one JSON object per line, tagged with its store.
+pub fn export(dir: &Path, out: &mut impl io::Write) -> io::Result<()> {
+    for store in JSON_STORES {
+        for entry in load_json(&dir.join(store.file)) {
+            let mut tagged = Map::new();
+            tagged.insert("store".into(), store.name.into());
+            tagged.extend(entry);
+            writeln!(out, "{}", Value::Object(tagged))?;
+        }
+    }
+    for line in AUDIT_LOGS
+        .iter()
+        .flat_map(|file| audit_lines(&dir.join(file)))
+    {
+        let fields: Vec<&str> = line.splitn(5, '\t').collect();
+        let [at, source, cwd, rule, command] = fields[..] else {
+            continue;
+        };
+        let entry = serde_json::json!({
+            "store": "policy_audit",
+            "at": at.parse::<u64>().ok(),
+            "source": source,
+            "cwd": cwd,
+            "rule": rule,
+            "command": command,
+        });
+        writeln!(out, "{entry}")?;
+    }
+    for file in LLM_AUDIT_LOGS {
+        for exchange in load_llm_audit(&dir.join(file)) {
+            let mut tagged = Map::new();
+            tagged.insert("store".into(), "llm_audit".into());
+            tagged.extend(exchange);
+            writeln!(out, "{}", Value::Object(tagged))?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove matching entries from every store. Returns `(store, removed)` for
+/// stores that changed.
+pub fn forget(dir: &Path, filter: &Filter) -> io::Result<Vec<(&'static str, usize)>> {
+    let mut removed = Vec::new();
+    for store in JSON_STORES {
+        let path = dir.join(store.file);
+        let _lock = lock_store(&path)?;
+        let entries = load_json(&path);
+        let before = entries.len();
+        let kept: Vec<Map<String, Value>> = entries
+            .into_iter()
+            .filter(|entry| {
+                let texts: Vec<&str> = store
+                    .text_fields
+                    .iter()
+                    .filter_map(|field| entry.get(*field)?.as_str())
+                    .collect();
+                let at = store.time_field.and_then(|f| entry.get(f)?.as_u64());
+                !filter.matches(&texts, at)
+            })
+            .collect();
+        if kept.len() < before {
+            write_atomic(&path, &serde_json::to_string(&kept)?)?;
+            removed.push((store.name, before - kept.len()));
+        }
+    }
+
+    let mut audit_removed = 0;
+    for file in AUDIT_LOGS {
+        let path = dir.join(file);
+        let lines = audit_lines(&path);
+        let before = lines.len();
+        let kept: Vec<String> = lines
+            .into_iter()
+            .filter(|line| {
+                let at = line.split('\t').next().and_then(|t| t.parse().ok());
+                !filter.matches(&[line.as_str()], at)
+            })
+            .collect();
+        if kept.len() < before {
+            let mut content = kept.join("\n");
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            write_atomic(&path, &content)?;
+            audit_removed += before - kept.len();
+        }
+    }
+    if audit_removed > 0 {
+        removed.push(("policy_audit", audit_removed));
+    }
+
+    let mut llm_removed = 0;
+    for file in LLM_AUDIT_LOGS {
+        let path = dir.join(file);
+        let exchanges = load_llm_audit(&path);
+        let before = exchanges.len();
+        let kept: Vec<String> = exchanges
+            .into_iter()
+            .filter(|exchange| {
+                // Prompts and responses are free text; match anywhere in them.
+                let text = Value::Object(exchange.clone()).to_string();
+                let at = exchange.get("at").and_then(Value::as_u64);
+                !filter.matches(&[text.as_str()], at)
+            })
+            .map(|exchange| Value::Object(exchange).to_string() + "\n")
+            .collect();
+        if kept.len() < before {
+            write_atomic(&path, &kept.concat())?;
+            llm_removed += before - kept.len();
+        }
+    }
+    if llm_removed > 0 {
+        removed.push(("llm_audit", llm_removed));
+    }
+    Ok(removed)
+}
+
+/// Parse `YYYY-MM-DD` as midnight UTC in Unix seconds.
+pub fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Days from civil date (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days * 86_400).ok()
+}
+
+fn load_json(path: &Path) -> Vec<Map<String, Value>> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn load_llm_audit(path: &Path) -> Vec<Map<String, Value>> {
+    audit_lines(path)
+        .iter()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn audit_lines(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Take an exclusive lock for a read-modify-write of the store at `path`,
+/// held until the returned file is dropped. Write-then-rename alone keeps
+/// readers from seeing a torn file, but two shells updating at once would
+/// each write back their own snapshot and lose the other's change.
+pub fn lock_store(path: &Path) -> io::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"))?;
+    lock.lock()?;
+    Ok(lock)
+}
+
+/// Write-then-rename so concurrent terminals never observe a torn file.
+fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-03-01"), Some(1_709_251_200));
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_forget_removes_matching_entries_everywhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let examples = dir.path().join("nl_examples.json");
+        crate::nl_examples::record(&examples, "ssh to db", "ssh db.internal.corp").unwrap();
+        crate::nl_examples::record(&examples, "list files", "ls -la").unwrap();
+        crate::durations::record(
+            &dir.path().join("durations.json"),
+            "ssh db.internal.corp",
+            30,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(AUDIT_LOGS[0]),
+            "100\tllm\t/tmp\trule\tscp x db.internal.corp:\n200\tllm\t/tmp\trule\tls\n",
+        )
+        .unwrap();
+        let exchange = crate::llm::audit::Exchange {
+            at: 300,
+            endpoint: "http://127.0.0.1:1234/v1/chat/completions".into(),
+            model: "local".into(),
+            messages: vec![("user".into(), "copy x to db.internal.corp".into())],
+            response: Some("scp x db.internal.corp:".into()),
+            error: None,
+        };
+        crate::llm::audit::record(&dir.path().join("llm-audit.jsonl"), exchange).unwrap();
+
+        let filter = Filter {
+            matching: Some(Regex::new(r"internal\.corp").unwrap()),
+            before: None,
+        };
+        let removed = forget(dir.path(), &filter).unwrap();
+        assert_eq!(
+            removed,
+            vec![
+                ("nl_examples", 1),
+                ("durations", 1),
+                ("policy_audit", 1),
+                ("llm_audit", 1)
+            ]
+        );
+
+        let mut out = Vec::new();
+        export(dir.path(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains("internal.corp"));
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.contains(r#""store":"policy_audit""#));
+    }
+}
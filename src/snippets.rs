@@ -0,0 +1,112 @@
+//! User-defined abbreviations (`kgp` -> `kubectl get pods -n <ns>`).
+//!
+//! Snippets live in `~/.synapse/snippets.toml` under a `[snippets]` table.
+//! Team snippets pulled by `synapse spec sync` are stored separately in
+//! `~/.synapse/team-snippets.toml`; a personal snippet with the same trigger
+//! wins. The plugin expands a trigger typed alone on the line when Space or
+//! Enter is pressed, leaving the cursor at the first `<placeholder>`; Enter
+//! only expands, so the command runs on the next Enter.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnippetFile {
+    #[serde(default)]
+    snippets: BTreeMap<String, String>,
+}
+
+pub fn snippets_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("snippets.toml"))
+}
+
+pub fn team_snippets_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("team-snippets.toml"))
+}
+
+/// Load snippets from one file. A missing or unreadable file is empty.
+pub fn load(path: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+/// Parse a snippet file, dropping entries with invalid triggers.
+pub fn parse(content: &str) -> Option<BTreeMap<String, String>> {
+    let mut file: SnippetFile = toml::from_str(content).ok()?;
+    file.snippets.retain(|trigger, _| is_valid_trigger(trigger));
+    Some(file.snippets)
+}
+
+/// Team snippets overlaid with the user's own.
+pub fn load_all() -> BTreeMap<String, String> {
+    let mut snippets = team_snippets_path().map(|p| load(&p)).unwrap_or_default();
+    snippets.extend(snippets_path().map(|p| load(&p)).unwrap_or_default());
+    snippets
+}
+
+/// Triggers are single words the plugin can match against the whole buffer.
+pub fn is_valid_trigger(trigger: &str) -> bool {
+    !trigger.is_empty()
+        && trigger
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '+' | ','))
+}
+
+/// Add or replace a snippet.
+pub fn add(path: &Path, trigger: &str, expansion: &str) -> io::Result<()> {
+    let mut snippets = load(path);
+    snippets.insert(trigger.to_string(), expansion.to_string());
+    save(path, snippets)
+}
+
+/// Remove a snippet; returns whether it existed.
+pub fn remove(path: &Path, trigger: &str) -> io::Result<bool> {
+    let mut snippets = load(path);
+    let existed = snippets.remove(trigger).is_some();
+    if existed {
+        save(path, snippets)?;
+    }
+    Ok(existed)
+}
+
+pub fn save(path: &Path, snippets: BTreeMap<String, String>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = toml::to_string(&SnippetFile { snippets }).map_err(io::Error::other)?;
+    // Write-then-rename so concurrent terminals never observe a torn file.
+    let tmp = path.with_extension(format!("toml.{}", std::process::id()));
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_remove_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snippets.toml");
+        add(&path, "kgp", "kubectl get pods -n <ns>").unwrap();
+        add(&path, "gst", "git status").unwrap();
+        assert_eq!(
+            load(&path).get("kgp").map(String::as_str),
+            Some("kubectl get pods -n <ns>")
+        );
+        assert!(remove(&path, "gst").unwrap());
+        assert!(!remove(&path, "gst").unwrap());
+        assert_eq!(load(&path).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_drops_invalid_triggers() {
+        let snippets = parse("[snippets]\nok = \"ls\"\n\"two words\" = \"x\"\n").unwrap();
+        assert_eq!(snippets.keys().collect::<Vec<_>>(), vec!["ok"]);
+    }
+}
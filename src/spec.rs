@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 pub enum SpecSource {
     Discovered,
     ProjectAuto,
+    /// Installed from a spec bundle (`synapse spec import`).
+    Imported,
 }
 
 /// Root command specification
@@ -25,6 +27,10 @@ pub struct CommandSpec {
     /// Set at load time, not from TOML
     #[serde(skip)]
     pub source: SpecSource,
+    /// Directory the spec belongs to (project-local scripts); elsewhere the
+    /// completion falls back to default file completion. Not from TOML.
+    #[serde(skip)]
+    pub project_root: Option<std::path::PathBuf>,
 }
 
 impl Default for CommandSpec {
@@ -37,6 +43,7 @@ impl Default for CommandSpec {
             args: Vec::new(),
             recursive: false,
             source: SpecSource::ProjectAuto,
+            project_root: None,
         }
     }
 }
@@ -72,6 +79,14 @@ pub struct OptionSpec {
     pub takes_arg: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub arg_generator: Option<GeneratorSpec>,
+    /// Fixed values for the option's argument (e.g. `--format json|yaml`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arg_suggestions: Vec<ArgValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arg_template: Option<ArgTemplate>,
+    /// Other flags that cannot be combined with this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclusive_with: Vec<String>,
 }
 
 /// Argument position definition
@@ -81,14 +96,52 @@ pub struct ArgSpec {
     pub name: String,
     #[serde(default, skip_serializing_if = "is_false")]
     pub variadic: bool,
+    /// May be left out (`gh pr view` picks the current branch's PR).
+    /// Variadic args are always optional.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub optional: bool,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub suggestions: Vec<String>,
+    pub suggestions: Vec<ArgValue>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub generator: Option<GeneratorSpec>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub template: Option<ArgTemplate>,
 }
 
+/// Fixed argument value, optionally described (e.g. a documented Makefile target).
+/// Also read from a plain string, the form specs used before descriptions.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(from = "ArgValueRepr")]
+pub struct ArgValue {
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ArgValueRepr {
+    Plain(String),
+    Described {
+        #[serde(default)]
+        value: String,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+impl From<ArgValueRepr> for ArgValue {
+    fn from(repr: ArgValueRepr) -> Self {
+        match repr {
+            ArgValueRepr::Plain(value) => Self {
+                value,
+                description: None,
+            },
+            ArgValueRepr::Described { value, description } => Self { value, description },
+        }
+    }
+}
+
 /// Dynamic value generator
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
@@ -101,6 +154,8 @@ pub struct GeneratorSpec {
     pub split_on: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub strip_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "GeneratorFormat::is_lines")]
+    pub format: GeneratorFormat,
 }
 
 impl Default for GeneratorSpec {
@@ -109,10 +164,43 @@ impl Default for GeneratorSpec {
             command: String::new(),
             split_on: default_split_on(),
             strip_prefix: None,
+            format: GeneratorFormat::Lines,
         }
     }
 }
 
+/// How each generator output item is read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GeneratorFormat {
+    /// The whole item is the value
+    #[default]
+    Lines,
+    /// `value<TAB>description`; the description is optional
+    Tsv,
+}
+
+impl GeneratorFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lines" => Some(Self::Lines),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lines => "lines",
+            Self::Tsv => "tsv",
+        }
+    }
+
+    fn is_lines(&self) -> bool {
+        *self == Self::Lines
+    }
+}
+
 fn default_split_on() -> String {
     "\n".to_string()
 }
@@ -133,4 +221,50 @@ pub enum ArgTemplate {
     Directories,
     EnvVars,
     History,
+    /// Files matching a glob, e.g. `{ file_glob = "*.tfvars" }`
+    FileGlob(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggestions_accept_plain_strings() {
+        let spec: CommandSpec = toml::from_str(
+            r#"
+name = "deployctl"
+
+[[options]]
+long = "--format"
+takes_arg = true
+arg_suggestions = ["json", { value = "yaml", description = "YAML output" }]
+
+[[args]]
+name = "env"
+suggestions = ["staging", "prod"]
+"#,
+        )
+        .unwrap();
+        let plain = |value: &str| ArgValue {
+            value: value.into(),
+            description: None,
+        };
+        assert_eq!(
+            spec.args[0].suggestions,
+            vec![plain("staging"), plain("prod")]
+        );
+        assert_eq!(spec.options[0].arg_suggestions[0], plain("json"));
+        assert_eq!(
+            spec.options[0].arg_suggestions[1].description.as_deref(),
+            Some("YAML output")
+        );
+
+        let reparsed: CommandSpec = toml::from_str(&toml::to_string(&spec).unwrap()).unwrap();
+        assert_eq!(reparsed.args[0].suggestions, spec.args[0].suggestions);
+        assert_eq!(
+            reparsed.options[0].arg_suggestions,
+            spec.options[0].arg_suggestions
+        );
+    }
 }
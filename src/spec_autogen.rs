@@ -1,12 +1,31 @@
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use crate::spec::{ArgSpec, CommandSpec, GeneratorSpec, OptionSpec, SubcommandSpec};
+use crate::config::SpecConfig;
+use crate::spec::{
+    ArgSpec, ArgTemplate, ArgValue, CommandSpec, GeneratorFormat, GeneratorSpec, OptionSpec,
+    SubcommandSpec,
+};
+
+// GNU make's lookup order.
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+const COMPOSE_FILES: &[&str] = &[
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "compose.yml",
+    "compose.yaml",
+];
+const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
 
 /// Auto-generate specs from project files.
 ///
 /// Only generates specs for dynamic tools that use generators to read
 /// project-specific config at completion time (Makefile targets, npm scripts,
-/// docker-compose services, just recipes). Static tools like cargo, pytest,
+/// docker-compose services, just recipes, terraform workspaces and resource
+/// addresses). Makefile targets come from `synapse make-targets`, which
+/// parses the Makefile at TAB time so `## comment` doc strings describe each
+/// target; `synapse package-scripts` likewise describes each package.json
+/// script by its command. Static tools like cargo, pytest,
 /// poetry, and ruff are better served by their own completion generators
 /// or system zsh completion files, which are far more comprehensive than
 /// any hardcoded spec we could maintain.
@@ -15,26 +34,21 @@ use crate::spec::{ArgSpec, CommandSpec, GeneratorSpec, OptionSpec, SubcommandSpe
 /// `npm run`, `docker compose`, and `just` resolve their config relative to
 /// CWD, so we parse from there to match what the user would actually see
 /// (important in monorepos where subdirectories have their own config files).
-pub fn generate_specs(cwd: &Path) -> Vec<CommandSpec> {
+pub fn generate_specs(cwd: &Path, config: &SpecConfig) -> Vec<CommandSpec> {
     let mut specs = Vec::new();
-    const MAKEFILES: &[&str] = &["Makefile", "makefile", "GNUmakefile"];
-    const COMPOSE_FILES: &[&str] = &[
-        "docker-compose.yml",
-        "docker-compose.yaml",
-        "compose.yml",
-        "compose.yaml",
-    ];
-    const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
 
-    // Dynamic tools: detect file existence only, completions use generators
-    // that run at completion time (always current for the cwd).
-    if crate::project::has_any_file(cwd, MAKEFILES) {
+    // Dynamic tools: detect file existence only and use generators that run
+    // at completion time (always current for the cwd).
+    if find_makefile(cwd).is_some() {
         specs.push(make_spec());
     }
 
-    if cwd.join("package.json").exists() {
+    if cwd.join("package.json").is_file() {
         let manager = crate::project::detect_package_manager(cwd);
-        specs.push(package_json_spec(manager));
+        specs.push(package_json_spec(manager, config.registry_completions));
+        if cwd.join(NODE_BIN_DIR).is_dir() {
+            specs.push(npx_spec());
+        }
     }
 
     if crate::project::has_any_file(cwd, COMPOSE_FILES) {
@@ -45,9 +59,34 @@ pub fn generate_specs(cwd: &Path) -> Vec<CommandSpec> {
         specs.push(justfile_spec());
     }
 
+    let has_tf_files = std::fs::read_dir(cwd).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| e.path().extension().is_some_and(|ext| ext == "tf"))
+    });
+    if has_tf_files {
+        specs.push(terraform_spec());
+    }
+
     specs
 }
 
+/// Paths whose changes can alter [`generate_specs`] or [`project_scripts`]
+/// for `cwd`, for `synapse scan --watch` to poll. `cwd` and the script
+/// directories are included because their modification times change when
+/// entries (`*.tf` files, scripts) are added or removed.
+pub fn spec_inputs(cwd: &Path) -> Vec<PathBuf> {
+    let files = MAKEFILES
+        .iter()
+        .chain(COMPOSE_FILES)
+        .chain(JUSTFILES)
+        .chain(&["package.json", NODE_BIN_DIR])
+        .chain(SCRIPT_DIRS);
+    std::iter::once(cwd.to_path_buf())
+        .chain(files.map(|name| cwd.join(name)))
+        .collect()
+}
+
 fn opt(short: Option<&str>, long: Option<&str>, description: &str, takes_arg: bool) -> OptionSpec {
     OptionSpec {
         short: short.map(str::to_string),
@@ -78,16 +117,38 @@ fn generated_arg(name: &str, command: &str, variadic: bool) -> ArgSpec {
     }
 }
 
+/// Like [`generated_arg`], for generators printing `value<TAB>description`.
+fn described_arg(name: &str, command: &str, variadic: bool) -> ArgSpec {
+    let mut arg = generated_arg(name, command, variadic);
+    if let Some(generator) = arg.generator.as_mut() {
+        generator.format = GeneratorFormat::Tsv;
+    }
+    arg
+}
+
+/// Lists make's targets from its database, for Makefiles the parser cannot
+/// read (generated rules, includes).
+pub const MAKE_QP_TARGETS: &str = "make -qp 2>/dev/null | awk -F: '/^[a-zA-Z][^$#\\/\\t=]*:([^=]|$)/{split($1,a,/ /);for(i in a)print a[i]}'";
+
+/// The Makefile make would read in `cwd`.
+pub fn find_makefile(cwd: &Path) -> Option<PathBuf> {
+    MAKEFILES
+        .iter()
+        .map(|name| cwd.join(name))
+        .find(|p| p.exists())
+}
+
+/// Targets are listed at TAB time by `synapse make-targets`, which parses the
+/// Makefile in the current directory (with `##` descriptions) and falls back
+/// to [`MAKE_QP_TARGETS`]. The `_make` file is shared by every directory, so
+/// it must not embed one project's targets.
 fn make_spec() -> CommandSpec {
+    let target_arg = described_arg("target", "synapse make-targets", true);
+
     CommandSpec {
         name: "make".to_string(),
         options: vec![
-            opt(
-                Some("-j"),
-                Some("--jobs"),
-                "Parallel jobs",
-                true,
-            ),
+            opt(Some("-j"), Some("--jobs"), "Parallel jobs", true),
             opt(
                 Some("-n"),
                 Some("--dry-run"),
@@ -95,26 +156,276 @@ fn make_spec() -> CommandSpec {
                 false,
             ),
         ],
-        args: vec![generated_arg(
-            "target",
-            "make -qp 2>/dev/null | awk -F: '/^[a-zA-Z][^$#\\/\\t=]*:([^=]|$)/{split($1,a,/ /);for(i in a)print a[i]}'",
-            true,
-        )],
+        args: vec![target_arg],
         ..Default::default()
     }
 }
 
-fn package_json_spec(manager: &str) -> CommandSpec {
-    let script_arg = generated_arg(
-        "script",
-        r#"node -e "Object.keys(require('./package.json').scripts||{}).forEach(s=>console.log(s))""#,
-        true,
-    );
+/// Parse Makefile rules into completable targets.
+///
+/// Descriptions come from the make-help convention: a trailing `## text` on
+/// the rule line, or `## text` comment lines directly above it. Simple
+/// variable references in target names (`$(BIN):`) are expanded. When the
+/// Makefile declares `.PHONY` targets, undocumented non-phony targets that
+/// look like build outputs (`app.o`) are dropped. Pattern rules, special
+/// targets, and anything still containing `$` after expansion are skipped.
+pub(crate) fn parse_makefile_targets(content: &str) -> Vec<ArgValue> {
+    let mut variables: HashMap<String, String> = HashMap::new();
+    let mut phony: HashSet<String> = HashSet::new();
+    let mut targets: Vec<ArgValue> = Vec::new();
+    let mut pending_doc: Vec<String> = Vec::new();
+
+    for line in join_continuations(content) {
+        if line.starts_with('\t') {
+            pending_doc.clear();
+            continue;
+        }
+        let trimmed = line.trim();
+        if let Some(doc) = trimmed.strip_prefix("##") {
+            pending_doc.push(doc.trim().to_string());
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            pending_doc.clear();
+            continue;
+        }
+
+        let (code, inline_doc) = match trimmed.split_once("##") {
+            Some((code, doc)) => (code.trim(), Some(doc.trim().to_string())),
+            None => (trimmed, None),
+        };
+        let code = code.split('#').next().unwrap_or("").trim();
+        let doc = inline_doc
+            .filter(|d| !d.is_empty())
+            .or_else(|| (!pending_doc.is_empty()).then(|| pending_doc.join(" ")));
+        pending_doc.clear();
+
+        if let Some((name, value)) = parse_variable_assignment(code) {
+            let value = expand_make_variables(value, &variables);
+            variables.insert(name.to_string(), value);
+            continue;
+        }
+
+        let Some((lhs, rhs)) = code.split_once(':') else {
+            continue;
+        };
+        let rhs = rhs.trim_start_matches(':');
+        // Target-specific variable assignment (`target: VAR = value`).
+        if rhs.contains('=') {
+            continue;
+        }
+
+        let lhs = expand_make_variables(lhs, &variables);
+        if lhs.trim() == ".PHONY" {
+            let deps = expand_make_variables(rhs, &variables);
+            phony.extend(deps.split_whitespace().map(str::to_string));
+            continue;
+        }
+
+        for name in lhs.split_whitespace() {
+            if name.starts_with('.') || name.contains(['%', '$', '/', '=']) {
+                continue;
+            }
+            if let Some(existing) = targets.iter_mut().find(|t| t.value == name) {
+                if existing.description.is_none() {
+                    existing.description = doc.clone();
+                }
+                continue;
+            }
+            targets.push(ArgValue {
+                value: name.to_string(),
+                description: doc.clone(),
+            });
+        }
+    }
+
+    if !phony.is_empty() {
+        targets.retain(|t| {
+            t.description.is_some() || phony.contains(&t.value) || !t.value.contains('.')
+        });
+    }
+    targets
+}
+
+/// Join backslash-continued lines, preserving a leading tab so recipe lines
+/// remain recognizable.
+fn join_continuations(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for raw in content.lines() {
+        if let Some(stripped) = raw.strip_suffix('\\') {
+            current.push_str(stripped);
+            current.push(' ');
+        } else {
+            current.push_str(raw);
+            lines.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Recognize `NAME = value` and its `:=`, `::=`, `?=`, `+=` variants.
+fn parse_variable_assignment(code: &str) -> Option<(&str, &str)> {
+    let eq = code.find('=')?;
+    let colon = code.find(':');
+    // `a: b=c` is a rule with a target-specific variable, not an assignment.
+    if colon.is_some_and(|c| c < eq && !code[c..eq].chars().all(|ch| ch == ':')) {
+        return None;
+    }
+    let name = code[..eq].trim_end_matches([':', '?', '+', '!']).trim();
+    let name = name
+        .strip_prefix("export ")
+        .or_else(|| name.strip_prefix("override "))
+        .unwrap_or(name)
+        .trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name, code[eq + 1..].trim()))
+}
+
+/// Expand `$(NAME)` and `${NAME}` references to known variables.
+/// Unknown references are left as-is.
+fn expand_make_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let close = match after.chars().next() {
+            Some('(') => ')',
+            Some('{') => '}',
+            _ => {
+                out.push('$');
+                rest = after;
+                continue;
+            }
+        };
+        match after.find(close) {
+            Some(end) => {
+                let name = &after[1..end];
+                match variables.get(name) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&rest[pos..pos + 1 + end + 1]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[pos..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Maximum characters of a script body shown as its description.
+const MAX_SCRIPT_PREVIEW_CHARS: usize = 60;
+
+/// Parse `scripts` from package.json, describing each script with its
+/// (truncated) command so users see what `npm run deploy` will execute.
+pub(crate) fn parse_package_scripts(content: &str) -> Vec<ArgValue> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(scripts) = package.get("scripts").and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+
+    scripts
+        .iter()
+        .map(|(name, body)| ArgValue {
+            value: name.clone(),
+            description: body.as_str().map(preview_script_body),
+        })
+        .collect()
+}
+
+fn preview_script_body(body: &str) -> String {
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if body.chars().count() <= MAX_SCRIPT_PREVIEW_CHARS {
+        return body;
+    }
+    let truncated: String = body.chars().take(MAX_SCRIPT_PREVIEW_CHARS - 3).collect();
+    format!("{truncated}...")
+}
+
+/// Directories scanned for project-local executables.
+const SCRIPT_DIRS: &[&str] = &["scripts", "bin"];
+
+/// Project-local executables in `cwd`: executable files directly under
+/// `scripts/` and `bin/`, plus package.json `bin` entries. Paths are returned
+/// as typed at the prompt (`./scripts/foo.sh`), sorted.
+pub fn project_scripts(cwd: &Path) -> Vec<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut scripts = Vec::new();
+    for dir in SCRIPT_DIRS {
+        let Ok(entries) = std::fs::read_dir(cwd.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let is_executable = entry
+                .metadata()
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0);
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if is_executable && !name.starts_with('.') {
+                scripts.push(format!("./{dir}/{name}"));
+            }
+        }
+    }
+
+    if let Ok(content) = std::fs::read_to_string(cwd.join("package.json")) {
+        for path in parse_package_bin(&content) {
+            let path = path.trim_start_matches("./");
+            if cwd.join(path).is_file() {
+                scripts.push(format!("./{path}"));
+            }
+        }
+    }
+
+    scripts.sort();
+    scripts.dedup();
+    scripts
+}
+
+/// Relative paths of package.json `bin` entries (a single path or a
+/// name-to-path map).
+fn parse_package_bin(content: &str) -> Vec<String> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    match &package["bin"] {
+        serde_json::Value::String(path) => vec![path.clone()],
+        serde_json::Value::Object(map) => map
+            .values()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Scripts are listed at TAB time by `synapse package-scripts`, described by
+/// their commands, so the shared `_npm`/`_yarn`/... files never embed one
+/// project's scripts.
+fn package_json_spec(manager: &str, registry_completions: bool) -> CommandSpec {
+    let script_arg = described_arg("script", "synapse package-scripts", true);
 
     let subcommands = if manager == "npm" {
         let mut run = sub("run", "Run a script");
         run.args = vec![script_arg.clone()];
-        vec![run]
+        let mut subcommands = vec![run];
+        if registry_completions {
+            let mut install = sub("install", "Install packages");
+            install.aliases = vec!["i".into(), "add".into()];
+            install.args = vec![generated_arg("package", "synapse registry npm", true)];
+            subcommands.push(install);
+        }
+        subcommands
     } else {
         // yarn/pnpm/bun: scripts are top-level args
         Vec::new()
@@ -134,6 +445,356 @@ fn package_json_spec(manager: &str) -> CommandSpec {
     }
 }
 
+/// Hand-written specs for tools whose completable values come from synapse
+/// listings rather than from their `--help`. Discovery (`synapse add`) uses
+/// these first; it refuses commands that already have a completion function,
+/// so a builtin spec never shadows the tool's own.
+pub fn builtin_spec(command: &str) -> Option<CommandSpec> {
+    match command {
+        "brew" => Some(brew_spec()),
+        "gh" => Some(gh_spec()),
+        "aws" => Some(aws_spec()),
+        "gcloud" => Some(CommandSpec {
+            name: "gcloud".to_string(),
+            options: vec![generated_opt(
+                "--project",
+                "Google Cloud project to use",
+                "synapse cloud gcp-projects",
+            )],
+            ..Default::default()
+        }),
+        "kubectl" => Some(kubectl_spec()),
+        "docker" => Some(CommandSpec {
+            name: "docker".to_string(),
+            subcommands: docker_container_subcommands(),
+            ..Default::default()
+        }),
+        _ => None,
+    }
+}
+
+fn generated_opt(long: &str, description: &str, command: &str) -> OptionSpec {
+    OptionSpec {
+        arg_generator: Some(GeneratorSpec {
+            command: command.to_string(),
+            ..Default::default()
+        }),
+        ..opt(None, Some(long), description, true)
+    }
+}
+
+/// Profiles, regions and buckets come from `synapse cloud`, which only
+/// calls AWS for regions and buckets when network completions are enabled.
+fn aws_spec() -> CommandSpec {
+    let account_opts = || {
+        vec![
+            generated_opt(
+                "--profile",
+                "Use a specific profile from your credential file",
+                "synapse cloud aws-profiles",
+            ),
+            generated_opt("--region", "The region to use", "synapse cloud aws-regions"),
+        ]
+    };
+    let bucket = || {
+        generated_arg(
+            "bucket",
+            "synapse cloud s3-buckets | sed 's|^|s3://|'",
+            false,
+        )
+    };
+
+    let mut s3 = sub("s3", "Manage S3 buckets and objects");
+    s3.options = account_opts();
+    for (name, description) in [
+        (
+            "ls",
+            "List S3 objects and common prefixes under a prefix or all S3 buckets",
+        ),
+        ("rb", "Delete an empty S3 bucket"),
+        ("rm", "Delete an S3 object"),
+    ] {
+        let mut subcommand = sub(name, description);
+        subcommand.options = account_opts();
+        // Without a bucket, `ls` lists the buckets themselves.
+        let mut arg = bucket();
+        arg.optional = name == "ls";
+        subcommand.args = vec![arg];
+        s3.subcommands.push(subcommand);
+    }
+
+    CommandSpec {
+        name: "aws".to_string(),
+        options: account_opts(),
+        subcommands: vec![s3],
+        ..Default::default()
+    }
+}
+
+fn kubectl_spec() -> CommandSpec {
+    let context_arg = || generated_arg("context", "synapse cloud kube-contexts", false);
+    let mut config = sub("config", "Modify kubeconfig files");
+    for (name, description) in [
+        (
+            "use-context",
+            "Set the current-context in a kubeconfig file",
+        ),
+        (
+            "delete-context",
+            "Delete the specified context from the kubeconfig",
+        ),
+        (
+            "rename-context",
+            "Rename a context from the kubeconfig file",
+        ),
+    ] {
+        let mut subcommand = sub(name, description);
+        subcommand.args = vec![context_arg()];
+        config.subcommands.push(subcommand);
+    }
+
+    CommandSpec {
+        name: "kubectl".to_string(),
+        options: vec![generated_opt(
+            "--context",
+            "The name of the kubeconfig context to use",
+            "synapse cloud kube-contexts",
+        )],
+        subcommands: vec![config],
+        ..Default::default()
+    }
+}
+
+/// PR and issue arguments are the repository's open ones, listed with their
+/// titles by `synapse gh`. Most `gh pr` subcommands default to the current
+/// branch's PR, so their argument is optional.
+fn gh_spec() -> CommandSpec {
+    let with_arg = |names: &[(&str, &str)], arg: &str, listing: &str| -> Vec<SubcommandSpec> {
+        names
+            .iter()
+            .map(|(name, description)| {
+                let mut subcommand = sub(name, description);
+                let mut arg = described_arg(arg, &format!("synapse gh {listing}"), false);
+                arg.optional = listing == "prs" && *name != "checkout";
+                subcommand.args = vec![arg];
+                subcommand
+            })
+            .collect()
+    };
+
+    let mut pr = sub("pr", "Manage pull requests");
+    pr.subcommands = with_arg(
+        &[
+            ("checkout", "Check out a pull request in git"),
+            ("view", "View a pull request"),
+            ("diff", "View changes in a pull request"),
+            ("checks", "Show CI status for a single pull request"),
+            ("review", "Add a review to a pull request"),
+            ("comment", "Add a comment to a pull request"),
+            ("edit", "Edit a pull request"),
+            ("ready", "Mark a pull request as ready for review"),
+            ("merge", "Merge a pull request"),
+            ("close", "Close a pull request"),
+            ("reopen", "Reopen a pull request"),
+        ],
+        "pr",
+        "prs",
+    );
+    pr.subcommands
+        .push(sub("list", "List pull requests in a repository"));
+    pr.subcommands.push(sub("create", "Create a pull request"));
+    pr.subcommands
+        .push(sub("status", "Show status of relevant pull requests"));
+
+    let mut issue = sub("issue", "Manage issues");
+    issue.subcommands = with_arg(
+        &[
+            ("view", "View an issue"),
+            ("comment", "Add a comment to an issue"),
+            ("edit", "Edit an issue"),
+            ("develop", "Manage linked branches for an issue"),
+            ("close", "Close an issue"),
+            ("reopen", "Reopen an issue"),
+        ],
+        "issue",
+        "issues",
+    );
+    issue
+        .subcommands
+        .push(sub("list", "List issues in a repository"));
+    issue.subcommands.push(sub("create", "Create a new issue"));
+    issue
+        .subcommands
+        .push(sub("status", "Show status of relevant issues"));
+
+    CommandSpec {
+        name: "gh".to_string(),
+        subcommands: vec![pr, issue],
+        ..Default::default()
+    }
+}
+
+fn brew_spec() -> CommandSpec {
+    let formula_arg = || generated_arg("formula", "synapse registry brew", true);
+    let installed_arg = || generated_arg("formula", "synapse registry brew-installed", true);
+    let kind_opts = || {
+        vec![
+            opt(
+                None,
+                Some("--formula"),
+                "Treat all named arguments as formulae",
+                false,
+            ),
+            opt(
+                None,
+                Some("--cask"),
+                "Treat all named arguments as casks",
+                false,
+            ),
+        ]
+    };
+
+    let mut subcommands = Vec::new();
+    for (name, description) in [
+        ("install", "Install a formula or cask"),
+        ("info", "Show information about a formula or cask"),
+        ("home", "Open a formula or cask's homepage"),
+        (
+            "fetch",
+            "Download the source packages for a formula or cask",
+        ),
+    ] {
+        let mut subcommand = sub(name, description);
+        subcommand.args = vec![formula_arg()];
+        subcommand.options = kind_opts();
+        subcommands.push(subcommand);
+    }
+    for (name, description) in [
+        ("uninstall", "Uninstall a formula or cask"),
+        ("upgrade", "Upgrade outdated formulae and casks"),
+        (
+            "reinstall",
+            "Uninstall and then reinstall a formula or cask",
+        ),
+        (
+            "link",
+            "Symlink a formula's installed files into the prefix",
+        ),
+        ("unlink", "Remove a formula's symlinks from the prefix"),
+        ("pin", "Prevent a formula from being upgraded"),
+        ("unpin", "Allow a pinned formula to be upgraded"),
+    ] {
+        let mut subcommand = sub(name, description);
+        subcommand.args = vec![installed_arg()];
+        subcommand.options = kind_opts();
+        if name == "uninstall" {
+            subcommand.aliases = vec!["remove".into(), "rm".into()];
+        }
+        subcommands.push(subcommand);
+    }
+    subcommands.extend([
+        sub("search", "Search for formulae and casks"),
+        sub("list", "List installed formulae and casks"),
+        sub(
+            "outdated",
+            "List installed formulae and casks with newer versions",
+        ),
+        sub(
+            "update",
+            "Fetch the newest version of Homebrew and all formulae",
+        ),
+        sub("cleanup", "Remove stale lock files and outdated downloads"),
+        sub("doctor", "Check your system for potential problems"),
+    ]);
+
+    CommandSpec {
+        name: "brew".to_string(),
+        subcommands,
+        ..Default::default()
+    }
+}
+
+/// Where package managers link the binaries of installed packages.
+const NODE_BIN_DIR: &str = "node_modules/.bin";
+
+fn npx_spec() -> CommandSpec {
+    CommandSpec {
+        name: "npx".to_string(),
+        args: vec![generated_arg(
+            "command",
+            "ls node_modules/.bin 2>/dev/null",
+            false,
+        )],
+        ..Default::default()
+    }
+}
+
+/// Binaries installed in `cwd`'s `node_modules/.bin`, runnable with `npx`
+/// or `pnpm exec`.
+pub fn node_bins(cwd: &Path) -> Vec<String> {
+    executables_in(&cwd.join(NODE_BIN_DIR))
+}
+
+/// Names of the executables in `dir`, sorted. Symlinks are followed, since
+/// `node_modules/.bin` and environment `bin` directories are mostly links.
+pub fn executables_in(dir: &Path) -> Vec<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            std::fs::metadata(entry.path())
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        })
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Whether `command` is an executable in some PATH directory.
+pub fn is_on_path(command: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path).any(|dir| {
+        std::fs::metadata(dir.join(command))
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    })
+}
+
+/// Containers used recently in any terminal (entity memory), then the
+/// running ones, without duplicates.
+const CONTAINER_GENERATOR: &str = "{ synapse memory list --kind container; docker ps --format '{{.Names}}' 2>/dev/null; } | awk '!seen[$0]++'";
+
+/// `docker` subcommands that take a container.
+fn docker_container_subcommands() -> Vec<SubcommandSpec> {
+    [
+        ("logs", "Fetch the logs of a container", false),
+        ("exec", "Execute a command in a running container", false),
+        ("attach", "Attach to a running container", false),
+        ("inspect", "Show low-level information on containers", true),
+        ("start", "Start stopped containers", true),
+        ("stop", "Stop running containers", true),
+        ("restart", "Restart containers", true),
+        ("kill", "Kill running containers", true),
+        ("rm", "Remove containers", true),
+    ]
+    .into_iter()
+    .map(|(name, description, variadic)| {
+        let mut subcommand = sub(name, description);
+        subcommand.args = vec![generated_arg("container", CONTAINER_GENERATOR, variadic)];
+        subcommand
+    })
+    .collect()
+}
+
 fn docker_compose_spec() -> CommandSpec {
     let service_arg = || {
         generated_arg(
@@ -168,13 +829,18 @@ fn docker_compose_spec() -> CommandSpec {
         build,
     ];
 
+    // This file becomes `_docker`, so keep container completion alongside
+    // `docker compose`.
+    let mut docker_subcommands = docker_container_subcommands();
+    docker_subcommands.push(SubcommandSpec {
+        name: "compose".into(),
+        subcommands,
+        ..Default::default()
+    });
+
     CommandSpec {
         name: "docker".to_string(),
-        subcommands: vec![SubcommandSpec {
-            name: "compose".into(),
-            subcommands,
-            ..Default::default()
-        }],
+        subcommands: docker_subcommands,
         ..Default::default()
     }
 }
@@ -190,3 +856,346 @@ fn justfile_spec() -> CommandSpec {
         ..Default::default()
     }
 }
+
+/// Project specs that may not cover everything their command's own
+/// completion does. `synapse scan` writes them only when no other
+/// completion for the command is installed, so they never shadow one.
+pub const DEFERRING_SPECS: &[&str] = &["terraform"];
+
+fn terraform_spec() -> CommandSpec {
+    // `terraform workspace list` marks the current workspace with `* `.
+    let workspace_arg = || ArgSpec {
+        name: "workspace".to_string(),
+        generator: Some(GeneratorSpec {
+            command: "terraform workspace list 2>/dev/null".to_string(),
+            strip_prefix: Some("* ".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let address_arg = |variadic| generated_arg("address", "synapse terraform-addresses", variadic);
+    let target_opt = || OptionSpec {
+        long: Some("-target".to_string()),
+        description: Some("Limit to the given resource address".to_string()),
+        takes_arg: true,
+        arg_generator: Some(GeneratorSpec {
+            command: "synapse terraform-addresses".to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let var_file_opt = || OptionSpec {
+        long: Some("-var-file".to_string()),
+        description: Some("Load variable values from the given file".to_string()),
+        takes_arg: true,
+        arg_template: Some(ArgTemplate::FileGlob("*.tfvars(|.json)".to_string())),
+        ..Default::default()
+    };
+
+    let mut select = sub("select", "Select a workspace");
+    select.args = vec![workspace_arg()];
+    let mut delete = sub("delete", "Delete a workspace");
+    delete.args = vec![workspace_arg()];
+    let mut workspace = sub("workspace", "Workspace management");
+    workspace.subcommands = vec![
+        sub("list", "List workspaces"),
+        sub("new", "Create a new workspace"),
+        select,
+        delete,
+        sub("show", "Show the name of the current workspace"),
+    ];
+
+    let mut show = sub("show", "Show a resource in the state");
+    show.args = vec![address_arg(false)];
+    let mut rm = sub("rm", "Remove instances from the state");
+    rm.args = vec![address_arg(true)];
+    let mut mv = sub("mv", "Move an item in the state");
+    mv.args = vec![address_arg(false)];
+    let mut state = sub("state", "Advanced state management");
+    state.subcommands = vec![sub("list", "List resources in the state"), show, rm, mv];
+
+    let mut taint = sub("taint", "Mark a resource instance as not fully functional");
+    taint.args = vec![address_arg(false)];
+    let mut untaint = sub(
+        "untaint",
+        "Remove the 'tainted' state from a resource instance",
+    );
+    untaint.args = vec![address_arg(false)];
+
+    let mut targeted = Vec::new();
+    for (name, description) in [
+        ("plan", "Show changes required by the current configuration"),
+        ("apply", "Create or update infrastructure"),
+        ("destroy", "Destroy previously-created infrastructure"),
+    ] {
+        let mut subcommand = sub(name, description);
+        subcommand.options = vec![target_opt(), var_file_opt()];
+        targeted.push(subcommand);
+    }
+
+    let mut import = sub(
+        "import",
+        "Associate existing infrastructure with a resource",
+    );
+    import.options = vec![var_file_opt()];
+    import.args = vec![address_arg(false)];
+    let mut refresh = sub("refresh", "Update the state to match remote systems");
+    refresh.options = vec![target_opt(), var_file_opt()];
+
+    let mut subcommands = vec![
+        sub("init", "Prepare your working directory for other commands"),
+        sub("validate", "Check whether the configuration is valid"),
+        sub("fmt", "Reformat your configuration in the standard style"),
+        sub("output", "Show output values from your root module"),
+        sub(
+            "console",
+            "Try Terraform expressions at an interactive command prompt",
+        ),
+        sub("get", "Install or upgrade remote Terraform modules"),
+        sub(
+            "graph",
+            "Generate a Graphviz graph of the steps in an operation",
+        ),
+        sub(
+            "providers",
+            "Show the providers required for this configuration",
+        ),
+        sub("test", "Execute integration tests for Terraform modules"),
+        sub(
+            "force-unlock",
+            "Release a stuck lock on the current workspace",
+        ),
+        sub("login", "Obtain and save credentials for a remote host"),
+        sub(
+            "logout",
+            "Remove locally-stored credentials for a remote host",
+        ),
+        sub("version", "Show the current Terraform version"),
+        workspace,
+        state,
+        taint,
+        untaint,
+        import,
+        refresh,
+    ];
+    subcommands.extend(targeted);
+
+    CommandSpec {
+        name: "terraform".to_string(),
+        subcommands,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target<'a>(targets: &'a [ArgValue], name: &str) -> Option<&'a ArgValue> {
+        targets.iter().find(|t| t.value == name)
+    }
+
+    #[test]
+    fn test_brew_spec_uses_registry_listings() {
+        let spec = builtin_spec("brew").unwrap();
+        let generator = |name: &str| {
+            let sub = spec.subcommands.iter().find(|s| s.name == name).unwrap();
+            sub.args[0].generator.as_ref().unwrap().command.clone()
+        };
+        assert_eq!(generator("install"), "synapse registry brew");
+        assert_eq!(generator("uninstall"), "synapse registry brew-installed");
+        let uninstall = spec.subcommands.iter().find(|s| s.name == "uninstall");
+        assert_eq!(uninstall.unwrap().aliases, vec!["remove", "rm"]);
+        assert!(builtin_spec("git").is_none());
+    }
+
+    #[test]
+    fn test_cloud_specs_use_cloud_listings() {
+        let option_generator = |spec: &CommandSpec, long: &str| {
+            let option = spec
+                .options
+                .iter()
+                .find(|o| o.long.as_deref() == Some(long));
+            option.unwrap().arg_generator.clone().unwrap().command
+        };
+        let aws = builtin_spec("aws").unwrap();
+        assert_eq!(
+            option_generator(&aws, "--profile"),
+            "synapse cloud aws-profiles"
+        );
+        let s3 = aws.subcommands.iter().find(|s| s.name == "s3").unwrap();
+        let ls = s3.subcommands.iter().find(|s| s.name == "ls").unwrap();
+        let buckets = &ls.args[0].generator.as_ref().unwrap().command;
+        assert!(buckets.starts_with("synapse cloud s3-buckets"));
+
+        let gcloud = builtin_spec("gcloud").unwrap();
+        assert_eq!(
+            option_generator(&gcloud, "--project"),
+            "synapse cloud gcp-projects"
+        );
+        let kubectl = builtin_spec("kubectl").unwrap();
+        assert_eq!(
+            option_generator(&kubectl, "--context"),
+            "synapse cloud kube-contexts"
+        );
+    }
+
+    #[test]
+    fn test_gh_spec_lists_open_prs_and_issues() {
+        let spec = builtin_spec("gh").unwrap();
+        let generator = |group: &str, name: &str| {
+            let group = spec.subcommands.iter().find(|s| s.name == group).unwrap();
+            let sub = group.subcommands.iter().find(|s| s.name == name).unwrap();
+            sub.args[0].generator.clone().unwrap()
+        };
+        let checkout = generator("pr", "checkout");
+        assert_eq!(checkout.command, "synapse gh prs");
+        assert_eq!(checkout.format, GeneratorFormat::Tsv);
+        assert_eq!(generator("issue", "close").command, "synapse gh issues");
+    }
+
+    #[test]
+    fn test_docker_logs_completes_remembered_containers() {
+        let spec = builtin_spec("docker").unwrap();
+        let logs = spec.subcommands.iter().find(|s| s.name == "logs").unwrap();
+        let generator = logs.args[0].generator.as_ref().unwrap();
+        assert!(generator
+            .command
+            .contains("synapse memory list --kind container"));
+    }
+
+    #[test]
+    fn test_project_scripts() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("scripts")).unwrap();
+        std::fs::create_dir_all(dir.path().join("tools")).unwrap();
+        for (path, mode) in [
+            ("scripts/deploy.sh", 0o755),
+            ("scripts/notes.md", 0o644),
+            ("tools/cli.js", 0o644),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"bin": {"acme": "./tools/cli.js", "missing": "gone.js"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            project_scripts(dir.path()),
+            vec!["./scripts/deploy.sh", "./tools/cli.js"]
+        );
+    }
+
+    #[test]
+    fn test_terraform_spec_only_with_tf_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SpecConfig::default();
+        assert!(generate_specs(dir.path(), &config).is_empty());
+
+        std::fs::write(dir.path().join("main.tf"), "").unwrap();
+        let specs = generate_specs(dir.path(), &config);
+        let terraform = specs.iter().find(|s| s.name == "terraform").unwrap();
+        let taint = terraform
+            .subcommands
+            .iter()
+            .find(|s| s.name == "taint")
+            .unwrap();
+        assert_eq!(
+            taint.args[0].generator.as_ref().unwrap().command,
+            "synapse terraform-addresses"
+        );
+    }
+
+    #[test]
+    fn test_makefile_doc_comments() {
+        let makefile = "\
+.PHONY: build test
+
+## Compile the project
+build:
+\tcargo build
+
+test: build ## Run the test suite
+\tcargo test
+
+clean:
+\trm -rf target
+";
+        let targets = parse_makefile_targets(makefile);
+        assert_eq!(
+            target(&targets, "build").unwrap().description.as_deref(),
+            Some("Compile the project")
+        );
+        assert_eq!(
+            target(&targets, "test").unwrap().description.as_deref(),
+            Some("Run the test suite")
+        );
+        assert_eq!(target(&targets, "clean").unwrap().description, None);
+    }
+
+    #[test]
+    fn test_node_bins_follow_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("node_modules/eslint/bin");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::create_dir_all(dir.path().join(NODE_BIN_DIR)).unwrap();
+        let target = package.join("eslint.js");
+        std::fs::write(&target, "").unwrap();
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink(&target, dir.path().join(NODE_BIN_DIR).join("eslint")).unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+
+        assert_eq!(node_bins(dir.path()), vec!["eslint"]);
+        let specs = generate_specs(dir.path(), &SpecConfig::default());
+        assert!(specs.iter().any(|spec| spec.name == "npx"));
+    }
+
+    #[test]
+    fn test_package_scripts_preview() {
+        let long = "x".repeat(100);
+        let json = format!(
+            r#"{{"scripts": {{"deploy": "npm run build &&\n  firebase deploy", "long": "{long}"}}}}"#
+        );
+        let scripts = parse_package_scripts(&json);
+        assert_eq!(
+            target(&scripts, "deploy").unwrap().description.as_deref(),
+            Some("npm run build && firebase deploy")
+        );
+        let preview = target(&scripts, "long")
+            .unwrap()
+            .description
+            .clone()
+            .unwrap();
+        assert_eq!(preview.chars().count(), MAX_SCRIPT_PREVIEW_CHARS);
+        assert!(preview.ends_with("..."));
+        assert!(parse_package_scripts("not json").is_empty());
+    }
+
+    #[test]
+    fn test_makefile_variable_targets_and_phony_filtering() {
+        let makefile = "\
+BIN := server
+.PHONY: run
+$(BIN): main.o
+\tcc -o $@ $^
+main.o: main.c
+\tcc -c $<
+run: $(BIN)
+\t./$(BIN)
+%.o: %.c
+\tcc -c $<
+debug: CFLAGS = -g
+";
+        let targets = parse_makefile_targets(makefile);
+        let names: Vec<&str> = targets.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(names, vec!["server", "run"]);
+    }
+}
@@ -0,0 +1,392 @@
+//! Shareable spec bundles: a `CommandSpec` packaged as TOML with version and
+//! generator trust metadata, so teams can distribute curated specs for
+//! internal CLIs.
+//!
+//! `synapse spec export` writes a bundle; `synapse spec import` validates one,
+//! records where it came from, stores it in `~/.synapse/specs/`, and writes
+//! the compsys completion file. Generators run shell commands at TAB time, so
+//! an imported bundle's generators are dropped unless the user trusts them.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::spec::{ArgSpec, CommandSpec, OptionSpec, SpecSource, SubcommandSpec};
+
+/// Bundle format version written by this build; newer bundles are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("invalid bundle: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error(
+        "bundle format version {0} is newer than supported ({FORMAT_VERSION}); update synapse"
+    )]
+    UnsupportedVersion(u32),
+    #[error("invalid command name '{0}'")]
+    InvalidName(String),
+    #[error("invalid option name '{0}'")]
+    InvalidOption(String),
+    #[error("invalid argument name '{0}'")]
+    InvalidArg(String),
+    #[error("invalid alias '{0}'")]
+    InvalidAlias(String),
+    #[error("invalid subcommand name '{0}'")]
+    InvalidSubcommand(String),
+    #[error("bundle declares generators that do not match its spec")]
+    GeneratorMismatch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecBundle {
+    pub bundle: BundleMeta,
+    pub spec: CommandSpec,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleMeta {
+    pub format_version: u32,
+    /// synapse version that exported the bundle.
+    pub synapse_version: String,
+    /// Unix timestamp (seconds) of the export.
+    pub exported_at: u64,
+    /// Every generator command in the spec, listed so importers can review
+    /// what would run before trusting the bundle.
+    #[serde(default)]
+    pub generators: Vec<String>,
+    /// Set on import; absent in exported bundles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    /// File path or URL the bundle was imported from.
+    pub source: String,
+    pub imported_at: u64,
+    /// Whether generators were kept (`--trust-generators`).
+    pub generators_trusted: bool,
+}
+
+pub fn specs_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("specs"))
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Package `spec` into a bundle.
+pub fn export(spec: &CommandSpec) -> SpecBundle {
+    SpecBundle {
+        bundle: BundleMeta {
+            format_version: FORMAT_VERSION,
+            synapse_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: now_secs(),
+            generators: generator_commands(spec),
+            provenance: None,
+        },
+        spec: spec.clone(),
+    }
+}
+
+/// Parse and validate a bundle.
+pub fn parse(content: &str) -> Result<SpecBundle, BundleError> {
+    let bundle: SpecBundle = toml::from_str(content)?;
+    if bundle.bundle.format_version > FORMAT_VERSION {
+        return Err(BundleError::UnsupportedVersion(
+            bundle.bundle.format_version,
+        ));
+    }
+    let name = &bundle.spec.name;
+    if !is_command_name(name) {
+        return Err(BundleError::InvalidName(name.clone()));
+    }
+    check_names(&bundle.spec)?;
+
+    let mut declared = bundle.bundle.generators.clone();
+    declared.sort();
+    declared.dedup();
+    if declared != generator_commands(&bundle.spec) {
+        return Err(BundleError::GeneratorMismatch);
+    }
+    Ok(bundle)
+}
+
+/// Record provenance, drop generators unless trusted, and store the bundle
+/// in `specs_dir`. Returns the spec ready for compsys export.
+pub fn install(
+    mut bundle: SpecBundle,
+    source: &str,
+    trust_generators: bool,
+    specs_dir: &Path,
+) -> std::io::Result<CommandSpec> {
+    if !trust_generators {
+        strip_generators(&mut bundle.spec);
+        bundle.bundle.generators.clear();
+    }
+    bundle.bundle.provenance = Some(Provenance {
+        source: source.to_string(),
+        imported_at: now_secs(),
+        generators_trusted: trust_generators,
+    });
+
+    std::fs::create_dir_all(specs_dir)?;
+    let content = toml::to_string(&bundle).map_err(std::io::Error::other)?;
+    std::fs::write(
+        specs_dir.join(format!("{}.toml", bundle.spec.name)),
+        content,
+    )?;
+
+    let mut spec = bundle.spec;
+    spec.source = SpecSource::Imported;
+    Ok(spec)
+}
+
+/// Whether `name` can be a command name: it becomes a file name and a
+/// `compdef` argument.
+fn is_command_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+}
+
+/// The first of `spec`'s aliases that already has a completion, in
+/// `completions_dir` or elsewhere on the fpath. An alias is registered with
+/// `compdef`, so it would otherwise take over that command's completion.
+pub fn taken_alias<'a>(spec: &'a CommandSpec, completions_dir: &Path) -> Option<&'a str> {
+    spec.aliases.iter().map(String::as_str).find(|alias| {
+        completions_dir.join(format!("_{alias}")).exists()
+            || crate::zsh_completion::has_completion_outside(alias, completions_dir)
+    })
+}
+
+/// The distinct generator commands in a spec, sorted.
+pub fn generator_commands(spec: &CommandSpec) -> Vec<String> {
+    fn visit(options: &[OptionSpec], args: &[ArgSpec], out: &mut Vec<String>) {
+        let option_generators = options.iter().filter_map(|o| o.arg_generator.as_ref());
+        let arg_generators = args.iter().filter_map(|a| a.generator.as_ref());
+        out.extend(
+            option_generators
+                .chain(arg_generators)
+                .map(|g| g.command.clone()),
+        );
+    }
+    fn visit_subcommands(subcommands: &[SubcommandSpec], out: &mut Vec<String>) {
+        for sub in subcommands {
+            visit(&sub.options, &sub.args, out);
+            visit_subcommands(&sub.subcommands, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(&spec.options, &spec.args, &mut out);
+    visit_subcommands(&spec.subcommands, &mut out);
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Reject aliases, subcommand, option and argument names that could not
+/// come from a real command line. They end up in generated completion
+/// functions, so this backs up the escaping done there.
+fn check_names(spec: &CommandSpec) -> Result<(), BundleError> {
+    fn valid_option(name: &str) -> bool {
+        name.starts_with('-')
+            && name.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | ':' | '+' | '=' | '-')
+            })
+    }
+    fn valid_arg(name: &str) -> bool {
+        name.chars().all(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(
+                    c,
+                    '_' | '.' | ':' | '+' | '=' | '-' | ' ' | '<' | '>' | '|' | '/'
+                )
+        })
+    }
+    fn check(options: &[OptionSpec], args: &[ArgSpec]) -> Result<(), BundleError> {
+        let flags = options
+            .iter()
+            .flat_map(|o| o.short.iter().chain(&o.long).chain(&o.exclusive_with));
+        if let Some(flag) = flags.into_iter().find(|flag| !valid_option(flag)) {
+            return Err(BundleError::InvalidOption(flag.clone()));
+        }
+        match args.iter().find(|a| !valid_arg(&a.name)) {
+            Some(arg) => Err(BundleError::InvalidArg(arg.name.clone())),
+            None => Ok(()),
+        }
+    }
+    fn check_subcommands(subcommands: &[SubcommandSpec]) -> Result<(), BundleError> {
+        for sub in subcommands {
+            // `heroku apps:create`-style names are allowed a colon.
+            let names = std::iter::once(&sub.name).chain(&sub.aliases);
+            if let Some(name) = names
+                .into_iter()
+                .find(|name| !is_command_name(&name.replace(':', "_")))
+            {
+                return Err(BundleError::InvalidSubcommand(name.clone()));
+            }
+            check(&sub.options, &sub.args)?;
+            check_subcommands(&sub.subcommands)?;
+        }
+        Ok(())
+    }
+
+    if let Some(alias) = spec.aliases.iter().find(|alias| !is_command_name(alias)) {
+        return Err(BundleError::InvalidAlias(alias.clone()));
+    }
+    check(&spec.options, &spec.args)?;
+    check_subcommands(&spec.subcommands)
+}
+
+fn strip_generators(spec: &mut CommandSpec) {
+    fn strip(options: &mut [OptionSpec], args: &mut [ArgSpec]) {
+        options.iter_mut().for_each(|o| o.arg_generator = None);
+        args.iter_mut().for_each(|a| a.generator = None);
+    }
+    fn strip_subcommands(subcommands: &mut [SubcommandSpec]) {
+        for sub in subcommands {
+            strip(&mut sub.options, &mut sub.args);
+            strip_subcommands(&mut sub.subcommands);
+        }
+    }
+
+    strip(&mut spec.options, &mut spec.args);
+    strip_subcommands(&mut spec.subcommands);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::GeneratorSpec;
+
+    fn deploy_spec() -> CommandSpec {
+        CommandSpec {
+            name: "deployctl".into(),
+            subcommands: vec![SubcommandSpec {
+                name: "rollout".into(),
+                description: Some("Roll out a service".into()),
+                args: vec![ArgSpec {
+                    name: "service".into(),
+                    generator: Some(GeneratorSpec {
+                        command: "deployctl services --plain".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_untrusted_import() {
+        let content = toml::to_string(&export(&deploy_spec())).unwrap();
+        let bundle = parse(&content).unwrap();
+        assert_eq!(bundle.bundle.generators, vec!["deployctl services --plain"]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let spec = install(
+            bundle,
+            "https://example.com/deployctl.toml",
+            false,
+            dir.path(),
+        )
+        .unwrap();
+        assert!(generator_commands(&spec).is_empty());
+        assert_eq!(spec.subcommands[0].name, "rollout");
+
+        let stored = std::fs::read_to_string(dir.path().join("deployctl.toml")).unwrap();
+        let provenance = parse(&stored).unwrap().bundle.provenance.unwrap();
+        assert_eq!(provenance.source, "https://example.com/deployctl.toml");
+        assert!(!provenance.generators_trusted);
+    }
+
+    #[test]
+    fn test_parse_rejects_undeclared_generators_and_bad_names() {
+        let mut bundle = export(&deploy_spec());
+        bundle.bundle.generators.clear();
+        let content = toml::to_string(&bundle).unwrap();
+        assert!(matches!(
+            parse(&content),
+            Err(BundleError::GeneratorMismatch)
+        ));
+
+        let mut bundle = export(&CommandSpec::default());
+        bundle.spec.name = "../evil".into();
+        let content = toml::to_string(&bundle).unwrap();
+        assert!(matches!(parse(&content), Err(BundleError::InvalidName(_))));
+    }
+
+    #[test]
+    fn test_parse_rejects_names_that_escape_quoting() {
+        let mut spec = deploy_spec();
+        spec.subcommands[0].options = vec![OptionSpec {
+            long: Some("--x'$(touch /tmp/pwned)'".into()),
+            description: Some("harmless".into()),
+            ..Default::default()
+        }];
+        let content = toml::to_string(&export(&spec)).unwrap();
+        assert!(matches!(
+            parse(&content),
+            Err(BundleError::InvalidOption(_))
+        ));
+
+        let mut spec = deploy_spec();
+        spec.subcommands[0].args[0].name = "service`id`".into();
+        let content = toml::to_string(&export(&spec)).unwrap();
+        assert!(matches!(parse(&content), Err(BundleError::InvalidArg(_))));
+
+        let mut spec = deploy_spec();
+        spec.options = vec![OptionSpec {
+            short: Some("-n".into()),
+            long: Some("--namespace".into()),
+            exclusive_with: vec!["--all-namespaces".into()],
+            ..Default::default()
+        }];
+        let content = toml::to_string(&export(&spec)).unwrap();
+        assert!(parse(&content).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_aliases_and_subcommands_that_escape_quoting() {
+        let mut spec = deploy_spec();
+        spec.aliases = vec!["dc; touch /tmp/pwned".into()];
+        let content = toml::to_string(&export(&spec)).unwrap();
+        assert!(matches!(parse(&content), Err(BundleError::InvalidAlias(_))));
+
+        let mut spec = deploy_spec();
+        spec.subcommands[0].aliases = vec!["ro$(id)".into()];
+        let content = toml::to_string(&export(&spec)).unwrap();
+        assert!(matches!(
+            parse(&content),
+            Err(BundleError::InvalidSubcommand(_))
+        ));
+
+        let mut spec = deploy_spec();
+        spec.aliases = vec!["dctl".into()];
+        spec.subcommands[0].name = "apps:create".into();
+        let content = toml::to_string(&export(&spec)).unwrap();
+        assert!(parse(&content).is_ok());
+    }
+
+    #[test]
+    fn test_aliases_cannot_claim_other_completions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("_git"), "#compdef git\n").unwrap();
+        let mut spec = deploy_spec();
+        spec.aliases = vec!["dctl".into(), "git".into()];
+        assert_eq!(taken_alias(&spec, dir.path()), Some("git"));
+        spec.aliases.pop();
+        assert_eq!(taken_alias(&spec, dir.path()), None);
+    }
+}
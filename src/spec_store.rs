@@ -12,6 +12,7 @@ mod help_parser;
 mod project_specs;
 mod sandbox;
 
+pub use discovery::DiscoveryMethod;
 pub use help_parser::parse_help_basic;
 pub use sandbox::sandbox_command;
 
@@ -1,9 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use tokio::process::Command;
 
-use crate::spec::{CommandSpec, SpecSource};
+use crate::spec::{CommandSpec, SpecSource, SubcommandSpec};
 
 use super::help_parser::parse_help_basic;
 use super::sandbox::{is_safe_command_name, sandbox_command};
@@ -70,6 +70,27 @@ const DISCOVERY_BLOCKLIST: &[&str] = &[
     "sqlite3",
 ];
 
+/// How [`SpecStore::discover_spec_via`] obtained a spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryMethod {
+    /// A spec shipped with synapse ([`crate::spec_autogen::builtin_spec`]).
+    Builtin,
+    /// The command's own zsh completion generator.
+    Generator,
+    /// Parsing `--help` output.
+    Help,
+}
+
+impl DiscoveryMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            DiscoveryMethod::Builtin => "builtin spec",
+            DiscoveryMethod::Generator => "completion generator",
+            DiscoveryMethod::Help => "--help",
+        }
+    }
+}
+
 /// Maximum bytes to read from --help stdout.
 const MAX_HELP_OUTPUT_BYTES: usize = 256 * 1024;
 
@@ -103,18 +124,107 @@ impl SpecStore {
     }
 
     /// Run discovery for a command and return the spec + compsys file path.
-    /// Tries completion generators first (structured), then `--help` regex.
+    /// Tries a builtin spec, then completion generators (structured), then
+    /// `--help` regex.
     pub async fn discover_command(&self, command: &str) -> Option<(CommandSpec, PathBuf)> {
+        let spec = self.discover_spec(command).await?;
+        self.write_discovered(command, spec)
+    }
+
+    /// Discover a spec without writing its completion file.
+    pub async fn discover_spec(&self, command: &str) -> Option<CommandSpec> {
+        self.discover_spec_via(command).await.map(|(spec, _)| spec)
+    }
+
+    /// Like [`Self::discover_spec`], also reporting which method succeeded.
+    pub async fn discover_spec_via(&self, command: &str) -> Option<(CommandSpec, DiscoveryMethod)> {
         if !self.can_discover_command(command) {
             return None;
         }
 
+        if let Some(mut spec) = crate::spec_autogen::builtin_spec(command) {
+            spec.source = SpecSource::Discovered;
+            return Some((spec, DiscoveryMethod::Builtin));
+        }
         if let Some(spec) = self.discover_with_generator(command).await {
-            return self.write_discovered(command, spec);
+            return Some((spec, DiscoveryMethod::Generator));
         }
-
         let spec = self.discover_with_help(command).await?;
-        self.write_discovered(command, spec)
+        Some((spec, DiscoveryMethod::Help))
+    }
+
+    /// Discover the subcommand at `path` (`["s3"]` for `aws s3`) by running
+    /// `<command> <path> --help`, for tools whose top-level help does not
+    /// describe their subcommands. The result replaces that subcommand in
+    /// `base`, creating any missing parents.
+    pub async fn discover_subcommand(
+        &self,
+        mut base: CommandSpec,
+        path: &[String],
+    ) -> Option<CommandSpec> {
+        let safe_path = !path.is_empty() && path.iter().all(|word| is_safe_command_name(word));
+        if !self.can_discover_command(&base.name) || !safe_path {
+            return None;
+        }
+
+        let timeout = Duration::from_millis(crate::config::DISCOVER_TIMEOUT_MS);
+        let help_text = self.fetch_help_output(&base.name, path, timeout).await?;
+        let parsed = parse_help_basic(path.last()?, &help_text);
+        if parsed.subcommands.is_empty() && parsed.options.is_empty() {
+            return None;
+        }
+
+        let sub = subcommand_at(&mut base.subcommands, path);
+        sub.subcommands = parsed.subcommands;
+        sub.options = parsed.options;
+        sub.args = parsed.args;
+        Some(base)
+    }
+
+    /// Whether `cwd` is inside a directory listed in `spec.trusted_projects`.
+    pub fn is_trusted_project(&self, cwd: &Path) -> bool {
+        let cwd = cwd.to_string_lossy();
+        self.config
+            .trusted_projects
+            .iter()
+            .any(|pattern| crate::security::compile_directory(pattern).is_match(&cwd))
+    }
+
+    /// Discover specs for project-local scripts (`./scripts/*`, `bin/*`,
+    /// package.json `bin`) by running them with `--help`. Scripts come from
+    /// the working tree, so this only runs in trusted projects. Specs are
+    /// named after the script's file name, which zsh also tries when
+    /// completing a command typed as a path, and only apply inside `cwd`, so
+    /// a same-named script or command elsewhere is not completed with them.
+    pub async fn discover_project_scripts(&self, cwd: &Path) -> Vec<CommandSpec> {
+        if !self.config.discover_from_help || !self.is_trusted_project(cwd) {
+            return Vec::new();
+        }
+
+        let timeout = Duration::from_millis(crate::config::DISCOVER_TIMEOUT_MS);
+        let mut specs = Vec::new();
+        for script in crate::spec_autogen::project_scripts(cwd) {
+            let path = cwd.join(&script);
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !is_safe_command_name(name) || self.zsh_index.contains(name) {
+                continue;
+            }
+            let Some(help_text) = self
+                .fetch_help_output(&path.to_string_lossy(), &[], timeout)
+                .await
+            else {
+                continue;
+            };
+            let mut spec = parse_help_basic(name, &help_text);
+            spec.source = SpecSource::ProjectAuto;
+            spec.project_root = Some(cwd.to_path_buf());
+            if !spec.subcommands.is_empty() || !spec.options.is_empty() {
+                specs.push(spec);
+            }
+        }
+        specs
     }
 
     fn write_discovered(&self, command: &str, spec: CommandSpec) -> Option<(CommandSpec, PathBuf)> {
@@ -188,3 +298,52 @@ impl SpecStore {
         None
     }
 }
+
+/// The subcommand at `path`, inserting empty entries for missing names.
+fn subcommand_at<'a>(
+    subcommands: &'a mut Vec<SubcommandSpec>,
+    path: &[String],
+) -> &'a mut SubcommandSpec {
+    let (name, rest) = path.split_first().expect("subcommand path is not empty");
+    let index = match subcommands.iter().position(|s| &s.name == name) {
+        Some(index) => index,
+        None => {
+            subcommands.push(SubcommandSpec {
+                name: name.clone(),
+                ..Default::default()
+            });
+            subcommands.len() - 1
+        }
+    };
+    let sub = &mut subcommands[index];
+    if rest.is_empty() {
+        sub
+    } else {
+        subcommand_at(&mut sub.subcommands, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subcommand_at_creates_missing_parents() {
+        let mut subcommands = vec![SubcommandSpec {
+            name: "s3".into(),
+            description: Some("Amazon S3".into()),
+            ..Default::default()
+        }];
+        let path = ["s3".to_string(), "cp".to_string()];
+        subcommand_at(&mut subcommands, &path).description = Some("Copy".into());
+        subcommand_at(&mut subcommands, &path[..1]);
+
+        assert_eq!(subcommands.len(), 1);
+        assert_eq!(subcommands[0].description.as_deref(), Some("Amazon S3"));
+        assert_eq!(subcommands[0].subcommands[0].name, "cp");
+        assert_eq!(
+            subcommands[0].subcommands[0].description.as_deref(),
+            Some("Copy")
+        );
+    }
+}
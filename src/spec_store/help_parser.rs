@@ -1,13 +1,13 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
-use crate::spec::{CommandSpec, OptionSpec, SubcommandSpec};
+use crate::spec::{ArgValue, CommandSpec, OptionSpec, SubcommandSpec};
 
 /// Minimal best-effort help text parser used when LLM is unavailable.
 /// Extracts obvious `--option` lines and `command  description` subcommand lines.
 pub fn parse_help_basic(command_name: &str, help_text: &str) -> CommandSpec {
     static OPT_RE: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"^\s*(-\w)?(?:\s*,\s*|\s+)?(--[\w][\w.-]*)?\s*(?:[=\s]\s*(\[?<?[\w.|/-]+>?\]?))?\s{2,}(.+)$").unwrap()
+        Regex::new(r"^\s*(-\w)?(?:\s*,\s*|\s+)?(--[\w][\w.-]*)?\s*(?:[=\s]\s*(\[?[<{]?[\w.|/,-]+[>}]?\]?))?\s{2,}(.+)$").unwrap()
     });
     static SUBCMD_RE: LazyLock<Regex> =
         LazyLock::new(|| Regex::new(r"^\s+([\w][\w.-]*)\s{2,}(.+)$").unwrap());
@@ -49,14 +49,17 @@ pub fn parse_help_basic(command_name: &str, help_text: &str) -> CommandSpec {
                     continue;
                 }
 
-                let takes_arg = caps.get(3).is_some();
-                let description = caps.get(4).map(|m| m.as_str().trim().to_string());
+                let placeholder = caps.get(3).map(|m| m.as_str());
+                let takes_arg = placeholder.is_some();
+                let (description, arg_suggestions) =
+                    split_possible_values(caps.get(4).map_or("", |m| m.as_str()), placeholder);
                 if short.is_some() || long.is_some() {
                     options.push(OptionSpec {
                         short,
                         long,
                         description,
                         takes_arg,
+                        arg_suggestions,
                         ..Default::default()
                     });
                     continue;
@@ -84,3 +87,75 @@ pub fn parse_help_basic(command_name: &str, help_text: &str) -> CommandSpec {
         ..Default::default()
     }
 }
+
+/// Pull enumerated values out of an option line: an argparse-style `{a,b}`
+/// placeholder, or a clap-style `[possible values: a, b]` suffix, which is
+/// dropped from the description.
+fn split_possible_values(
+    description: &str,
+    placeholder: Option<&str>,
+) -> (Option<String>, Vec<ArgValue>) {
+    let mut description = description.trim().to_string();
+    let mut values: Vec<String> = Vec::new();
+
+    if let Some(start) = description.find("[possible values:") {
+        if let Some(len) = description[start..].find(']') {
+            let list = &description[start + "[possible values:".len()..start + len];
+            values = list.split(',').map(|v| v.trim().to_string()).collect();
+            description.replace_range(start..=start + len, "");
+            description = description.trim().to_string();
+        }
+    }
+    if values.is_empty() {
+        if let Some(list) = placeholder.and_then(|p| p.strip_prefix('{')?.strip_suffix('}')) {
+            values = list.split(',').map(|v| v.trim().to_string()).collect();
+        }
+    }
+
+    let arg_suggestions = values
+        .into_iter()
+        .filter(|v| !v.is_empty())
+        .map(|value| ArgValue {
+            value,
+            description: None,
+        })
+        .collect();
+    let description = (!description.is_empty()).then_some(description);
+    (description, arg_suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_help_enumerated_option_values() {
+        let help = "\
+Options:
+  -o, --output {json,yaml}  Output format
+      --color <WHEN>        When to color [default: auto] [possible values: auto, always, never]
+";
+        let spec = parse_help_basic("tool", help);
+        let values = |long: &str| -> Vec<String> {
+            let opt = spec
+                .options
+                .iter()
+                .find(|o| o.long.as_deref() == Some(long))
+                .unwrap();
+            opt.arg_suggestions
+                .iter()
+                .map(|v| v.value.clone())
+                .collect()
+        };
+        assert_eq!(values("--output"), vec!["json", "yaml"]);
+        assert_eq!(values("--color"), vec!["auto", "always", "never"]);
+        let color = spec
+            .options
+            .iter()
+            .find(|o| o.long.as_deref() == Some("--color"));
+        assert_eq!(
+            color.unwrap().description.as_deref(),
+            Some("When to color [default: auto]")
+        );
+    }
+}
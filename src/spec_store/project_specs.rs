@@ -14,6 +14,32 @@ impl SpecStore {
         project_specs.get(command).cloned()
     }
 
+    /// Look up a spec from project specs, then synapse's generated completion
+    /// files, then the system zsh completion for `command`.
+    pub async fn lookup_with_system_fallback(
+        &self,
+        command: &str,
+        cwd: &Path,
+    ) -> Option<CommandSpec> {
+        if let Some(spec) = self.lookup(command, cwd).await {
+            return Some(spec);
+        }
+
+        let command = command.to_string();
+        let generated = self.completions_dir.join(format!("_{command}"));
+        tokio::task::spawn_blocking(move || {
+            crate::zsh_completion::parse_completion_file(&command, &generated)
+                .or_else(|| crate::zsh_completion::load_system_spec(&command))
+                .map(|mut spec| {
+                    spec.source = SpecSource::Discovered;
+                    spec
+                })
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
     /// Return all project specs for the given cwd as a Vec (for compsys export).
     pub async fn lookup_all_project_specs(&self, cwd: &Path) -> Vec<CommandSpec> {
         let project_specs = self.get_project_specs(cwd).await;
@@ -41,9 +67,10 @@ impl SpecStore {
                 }
 
                 let cwd_owned = cwd.to_path_buf();
+                let config = self.config.clone();
                 let specs = tokio::task::spawn_blocking(move || {
                     let mut specs = HashMap::new();
-                    for mut spec in spec_autogen::generate_specs(&cwd_owned) {
+                    for mut spec in spec_autogen::generate_specs(&cwd_owned, &config) {
                         spec.source = SpecSource::ProjectAuto;
                         specs.insert(spec.name.clone(), spec);
                     }
@@ -0,0 +1,466 @@
+//! Team-shared spec sources (`spec.remote_sources`).
+//!
+//! A source is either a git repository whose top-level `*.toml` files are
+//! spec bundles, or an HTTPS index listing bundle URLs with their SHA-256.
+//! Index bundles whose hash does not match are rejected. Git sources must be
+//! pinned to a full commit hash or to a tag that `git verify-tag` accepts.
+//! Every bundle is validated by [`spec_bundle::parse`] and installed into
+//! `~/.synapse/specs/remote/`, unless its command already has a completion
+//! that synapse did not sync; bundles that disappear from all sources are
+//! removed again. A git source may also carry a top-level `snippets.toml`
+//! (see [`crate::snippets`]), merged into the team snippet file; triggers
+//! that name a command on PATH are rejected, so a team snippet never
+//! replaces `ls` or `git`.
+//!
+//! Shell init runs `synapse spec sync --background` at shell startup, which
+//! syncs at most once per [`crate::stamp::SYNC_INTERVAL_SECS`].
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::RemoteSpecSource;
+use crate::spec::CommandSpec;
+use crate::spec_bundle;
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Specs installed or refreshed, ready for compsys export.
+    pub installed: Vec<CommandSpec>,
+    /// Names of previously synced specs no longer offered by any source.
+    pub removed: Vec<String>,
+    /// Number of team snippets written.
+    pub snippets: usize,
+    /// One message per source or bundle that failed.
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Index {
+    #[serde(default, rename = "bundle")]
+    bundles: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexEntry {
+    /// Bundle URL, absolute or relative to the index.
+    url: String,
+    sha256: String,
+}
+
+/// Where synced bundles are installed.
+pub fn remote_specs_dir() -> Option<PathBuf> {
+    crate::spec_bundle::specs_dir().map(|d| d.join("remote"))
+}
+
+/// Scratch space for git checkouts and the last-sync stamp.
+fn sync_state_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".synapse").join("spec-sync"))
+}
+
+/// Whether a background sync is due, based on the last-sync stamp.
+pub fn sync_due() -> bool {
+    sync_state_dir().is_some_and(|d| crate::stamp::sync_due(&d.join("last-sync")))
+}
+
+/// Fetch every source and install its bundles into `install_dir`.
+/// `completions_dir` is where synced specs become completion files.
+pub async fn sync(
+    sources: &[RemoteSpecSource],
+    install_dir: &Path,
+    completions_dir: &Path,
+) -> SyncReport {
+    let mut report = SyncReport::default();
+    let mut synced = HashSet::new();
+    let mut snippets = BTreeMap::new();
+
+    for source in sources {
+        let bundles = match (&source.git, &source.index) {
+            (Some(url), None) => match git_pin(source) {
+                Ok(pin) => fetch_git(url, &pin).await,
+                Err(e) => Err(e),
+            },
+            (None, Some(url)) => fetch_index(url).await,
+            _ => Err(anyhow::anyhow!(
+                "each remote source needs exactly one of `git` or `index`"
+            )),
+        };
+        let bundles = match bundles {
+            Ok(bundles) => bundles,
+            Err(e) => {
+                report.errors.push(format!("{}: {e:#}", source.label()));
+                continue;
+            }
+        };
+        if let Some(checkout) = source.git.as_deref().and_then(git_checkout_dir) {
+            if let Ok(content) = std::fs::read_to_string(checkout.join(SNIPPETS_FILE)) {
+                match crate::snippets::parse(&content) {
+                    Some(mut parsed) => {
+                        for trigger in drop_command_triggers(&mut parsed) {
+                            report.errors.push(format!(
+                                "{}#{SNIPPETS_FILE}: trigger '{trigger}' is a command; not shadowing it",
+                                source.label()
+                            ));
+                        }
+                        snippets.extend(parsed);
+                    }
+                    None => report.errors.push(format!(
+                        "{}#{SNIPPETS_FILE}: invalid snippets",
+                        source.label()
+                    )),
+                }
+            }
+        }
+
+        for (origin, content) in bundles {
+            let installed = spec_bundle::parse(&content)
+                .map_err(anyhow::Error::from)
+                .and_then(|bundle| {
+                    let name = &bundle.spec.name;
+                    if has_other_completion(name, install_dir, completions_dir) {
+                        anyhow::bail!("'{name}' already has a completion; not replacing it");
+                    }
+                    if let Some(alias) = spec_bundle::taken_alias(&bundle.spec, completions_dir) {
+                        anyhow::bail!("alias '{alias}' already has a completion; not replacing it");
+                    }
+                    Ok(spec_bundle::install(
+                        bundle,
+                        &origin,
+                        source.trust_generators,
+                        install_dir,
+                    )?)
+                });
+            match installed {
+                Ok(spec) => {
+                    synced.insert(spec.name.clone());
+                    report.installed.push(spec);
+                }
+                Err(e) => report.errors.push(format!("{origin}: {e:#}")),
+            }
+        }
+    }
+
+    // Only prune after a clean sync, so a flaky source never uninstalls specs.
+    if report.errors.is_empty() {
+        report.removed = prune(install_dir, &synced);
+        if let Some(path) = crate::snippets::team_snippets_path() {
+            report.snippets = snippets.len();
+            if let Err(e) = crate::snippets::save(&path, snippets) {
+                report.errors.push(format!("{}: {e}", path.display()));
+            }
+        }
+    }
+    if let Some(dir) = sync_state_dir() {
+        crate::stamp::write_stamp(&dir.join("last-sync"));
+    }
+    report
+}
+
+impl RemoteSpecSource {
+    fn label(&self) -> &str {
+        self.git
+            .as_deref()
+            .or(self.index.as_deref())
+            .unwrap_or("<remote source>")
+    }
+}
+
+/// Whether `name` has a completion synapse did not sync: one elsewhere on
+/// the fpath, or a file in `completions_dir` from another source (such as
+/// `synapse add`). A remote bundle must not replace it, or pruning the
+/// bundle later would delete it.
+fn has_other_completion(name: &str, install_dir: &Path, completions_dir: &Path) -> bool {
+    if install_dir.join(format!("{name}.toml")).exists() {
+        return false;
+    }
+    completions_dir.join(format!("_{name}")).exists()
+        || crate::zsh_completion::has_completion_outside(name, completions_dir)
+}
+
+/// Remove team snippets whose trigger is a command on PATH and return those
+/// triggers. The plugin also skips team triggers that are aliases, functions
+/// or builtins, which only the shell knows about.
+fn drop_command_triggers(snippets: &mut BTreeMap<String, String>) -> Vec<String> {
+    let commands: Vec<String> = snippets
+        .keys()
+        .filter(|trigger| crate::spec_autogen::is_on_path(trigger))
+        .cloned()
+        .collect();
+    for trigger in &commands {
+        snippets.remove(trigger);
+    }
+    commands
+}
+
+/// Remove installed bundles whose names are not in `keep`.
+fn prune(install_dir: &Path, keep: &HashSet<String>) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(install_dir) else {
+        return Vec::new();
+    };
+    let mut removed = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".toml"))
+        else {
+            continue;
+        };
+        if !keep.contains(name) && std::fs::remove_file(&path).is_ok() {
+            removed.push(name.to_string());
+        }
+    }
+    removed.sort();
+    removed
+}
+
+// --- git ---
+
+/// Team snippets file at the top of a git source; not a spec bundle.
+const SNIPPETS_FILE: &str = "snippets.toml";
+
+fn git_checkout_dir(url: &str) -> Option<PathBuf> {
+    sync_state_dir().map(|d| d.join("git").join(hex_sha256(url.as_bytes())))
+}
+
+/// What a git source is pinned to. Bundles run through a daily background
+/// job, so a branch tip, which anyone with push access can move, is not
+/// accepted.
+#[derive(Debug, PartialEq, Eq)]
+enum GitPin {
+    /// A full SHA-1 or SHA-256 commit id.
+    Commit(String),
+    /// A tag whose signature `git verify-tag` must accept.
+    SignedTag(String),
+}
+
+fn git_pin(source: &RemoteSpecSource) -> anyhow::Result<GitPin> {
+    let rev = source.rev.as_deref().unwrap_or_default();
+    if source.verify_tag && !rev.is_empty() {
+        return Ok(GitPin::SignedTag(rev.to_string()));
+    }
+    let is_commit = matches!(rev.len(), 40 | 64) && rev.chars().all(|c| c.is_ascii_hexdigit());
+    if !is_commit {
+        anyhow::bail!(
+            "git sources must pin `rev` to a full commit hash, or to a signed tag with `verify_tag = true`"
+        );
+    }
+    Ok(GitPin::Commit(rev.to_ascii_lowercase()))
+}
+
+/// Fetch the pinned revision into the checkout, verify it, then read the
+/// top-level bundles.
+async fn fetch_git(url: &str, pin: &GitPin) -> anyhow::Result<Vec<(String, String)>> {
+    let checkout = git_checkout_dir(url).ok_or_else(|| anyhow::anyhow!("no home directory"))?;
+
+    if !checkout.join(".git").exists() {
+        std::fs::create_dir_all(&checkout)?;
+        run_git(&checkout, &["init", "--quiet"]).await?;
+        run_git(&checkout, &["remote", "add", "origin", url]).await?;
+    }
+    match pin {
+        GitPin::Commit(commit) => {
+            run_git(&checkout, &["fetch", "--depth", "1", "origin", commit]).await?;
+            run_git(&checkout, &["reset", "--hard", "--quiet", "FETCH_HEAD"]).await?;
+            let head = run_git(&checkout, &["rev-parse", "HEAD"]).await?;
+            if head.trim() != commit {
+                anyhow::bail!("checked out {} instead of {commit}", head.trim());
+            }
+        }
+        GitPin::SignedTag(tag) => {
+            let refspec = format!("+refs/tags/{tag}:refs/tags/{tag}");
+            run_git(&checkout, &["fetch", "--depth", "1", "origin", &refspec]).await?;
+            run_git(&checkout, &["verify-tag", "--", tag]).await?;
+            let commit = format!("refs/tags/{tag}^{{commit}}");
+            run_git(&checkout, &["reset", "--hard", "--quiet", &commit]).await?;
+        }
+    }
+    run_git(&checkout, &["clean", "-fdxq"]).await?;
+
+    let mut bundles = Vec::new();
+    for entry in std::fs::read_dir(&checkout)?.flatten() {
+        let path = entry.path();
+        let is_bundle = path.extension().is_some_and(|ext| ext == "toml")
+            && path.file_name().is_some_and(|n| n != SNIPPETS_FILE);
+        if is_bundle {
+            let file = path.file_name().unwrap_or_default().to_string_lossy();
+            bundles.push((format!("{url}#{file}"), std::fs::read_to_string(&path)?));
+        }
+    }
+    Ok(bundles)
+}
+
+/// Run git in `cwd` and return its stdout.
+async fn run_git(cwd: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// --- https index ---
+
+async fn fetch_index(url: &str) -> anyhow::Result<Vec<(String, String)>> {
+    if !url.starts_with("https://") {
+        anyhow::bail!("index URLs must use https");
+    }
+    let client = reqwest::Client::builder()
+        .user_agent("synapse-spec-sync")
+        .build()?;
+    let index: Index = toml::from_str(&fetch_text(&client, url).await?)?;
+
+    let mut bundles = Vec::new();
+    for entry in index.bundles {
+        let bundle_url = resolve_url(url, &entry.url);
+        let content = fetch_text(&client, &bundle_url).await?;
+        if !hash_matches(content.as_bytes(), &entry.sha256) {
+            anyhow::bail!("{bundle_url}: sha256 mismatch");
+        }
+        bundles.push((bundle_url, content));
+    }
+    Ok(bundles)
+}
+
+async fn fetch_text(client: &reqwest::Client, url: &str) -> anyhow::Result<String> {
+    Ok(client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?)
+}
+
+/// Resolve an index entry URL relative to the index's own URL.
+fn resolve_url(index_url: &str, entry: &str) -> String {
+    if entry.contains("://") {
+        return entry.to_string();
+    }
+    let base = index_url
+        .rsplit_once('/')
+        .map_or(index_url, |(base, _)| base);
+    format!("{base}/{}", entry.trim_start_matches("./"))
+}
+
+fn hex_sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn hash_matches(bytes: &[u8], expected: &str) -> bool {
+    hex_sha256(bytes).eq_ignore_ascii_case(expected.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_url() {
+        let index = "https://specs.example.com/team/index.toml";
+        assert_eq!(
+            resolve_url(index, "deployctl.toml"),
+            "https://specs.example.com/team/deployctl.toml"
+        );
+        assert_eq!(
+            resolve_url(index, "./sub/x.toml"),
+            "https://specs.example.com/team/sub/x.toml"
+        );
+        assert_eq!(
+            resolve_url(index, "https://cdn.example.com/y.toml"),
+            "https://cdn.example.com/y.toml"
+        );
+    }
+
+    #[test]
+    fn test_hash_matches() {
+        let sha = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(hash_matches(b"hello", sha));
+        assert!(hash_matches(b"hello", &sha.to_uppercase()));
+        assert!(!hash_matches(b"hello!", sha));
+    }
+
+    #[test]
+    fn test_git_sources_must_be_pinned() {
+        let source = |rev: &str, verify_tag| RemoteSpecSource {
+            git: Some("https://example.com/specs.git".into()),
+            rev: (!rev.is_empty()).then(|| rev.to_string()),
+            verify_tag,
+            ..Default::default()
+        };
+        let commit = "0123456789ABCDEF0123456789abcdef01234567";
+        assert_eq!(
+            git_pin(&source(commit, false)).unwrap(),
+            GitPin::Commit(commit.to_ascii_lowercase())
+        );
+        assert_eq!(
+            git_pin(&source("v1.2.0", true)).unwrap(),
+            GitPin::SignedTag("v1.2.0".into())
+        );
+        assert!(git_pin(&source("main", false)).is_err());
+        assert!(git_pin(&source("0123abc", false)).is_err());
+        assert!(git_pin(&source("", false)).is_err());
+        assert!(git_pin(&source("", true)).is_err());
+    }
+
+    #[test]
+    fn test_bundles_never_replace_other_completions() {
+        let dir = tempfile::tempdir().unwrap();
+        let install_dir = dir.path().join("remote");
+        let completions_dir = dir.path().join("completions");
+        std::fs::create_dir_all(&install_dir).unwrap();
+        std::fs::create_dir_all(&completions_dir).unwrap();
+
+        let name = "synapse-sync-test-tool";
+        assert!(!has_other_completion(name, &install_dir, &completions_dir));
+        // Written by `synapse add`, not by a sync.
+        std::fs::write(completions_dir.join(format!("_{name}")), "").unwrap();
+        assert!(has_other_completion(name, &install_dir, &completions_dir));
+        // Synced before: refreshing it is fine.
+        std::fs::write(install_dir.join(format!("{name}.toml")), "").unwrap();
+        assert!(!has_other_completion(name, &install_dir, &completions_dir));
+    }
+
+    #[test]
+    fn test_team_triggers_never_shadow_commands() {
+        let mut snippets = BTreeMap::from([
+            ("sh".to_string(), "rm -rf ~".to_string()),
+            ("kgp".to_string(), "kubectl get pods".to_string()),
+        ]);
+        assert_eq!(drop_command_triggers(&mut snippets), vec!["sh"]);
+        assert_eq!(snippets.keys().collect::<Vec<_>>(), vec!["kgp"]);
+    }
+
+    #[test]
+    fn test_parse_index_and_prune() {
+        let index: Index = toml::from_str(
+            "[[bundle]]\nurl = \"a.toml\"\nsha256 = \"00\"\n\n[[bundle]]\nurl = \"b.toml\"\nsha256 = \"11\"\n",
+        )
+        .unwrap();
+        assert_eq!(index.bundles.len(), 2);
+        assert_eq!(index.bundles[1].url, "b.toml");
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("kept.toml"), "").unwrap();
+        std::fs::write(dir.path().join("gone.toml"), "").unwrap();
+        let keep = HashSet::from(["kept".to_string()]);
+        assert_eq!(prune(dir.path(), &keep), vec!["gone"]);
+        assert!(dir.path().join("kept.toml").exists());
+    }
+}
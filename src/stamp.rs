@@ -0,0 +1,48 @@
+//! Last-run stamps for the background jobs shell init launches on every
+//! startup (`spec sync --background`, `completions sync --background`), so
+//! each does its work at most once per [`SYNC_INTERVAL_SECS`].
+
+use std::path::Path;
+
+/// Minimum time between background syncs.
+pub const SYNC_INTERVAL_SECS: u64 = 86400;
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Whether the stamp at `path` is missing or older than [`SYNC_INTERVAL_SECS`].
+pub fn sync_due(path: &Path) -> bool {
+    let last = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    now_secs().saturating_sub(last) >= SYNC_INTERVAL_SECS
+}
+
+/// Record that the job behind `path` ran now.
+pub fn write_stamp(path: &Path) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, now_secs().to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stamp_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs").join("last-sync");
+        assert!(sync_due(&path));
+        write_stamp(&path);
+        assert!(!sync_due(&path));
+        std::fs::write(&path, "0").unwrap();
+        assert!(sync_due(&path));
+    }
+}
@@ -0,0 +1,313 @@
+//! Spec-based completions for a partially typed command line, for scripts,
+//! editor plugins and tests (`synapse suggest`).
+//!
+//! Only what the spec states statically is offered: subcommands, flags and
+//! fixed argument values, with values the user has passed to a flag before
+//! (see [`crate::flag_values`]) first. As in the exported compsys functions,
+//! flags already on the line and flags they exclude are not offered again.
+//! Generators and file paths are left to zsh.
+
+use serde::Serialize;
+
+use crate::flag_values::{flag_key, values_for, FlagValue};
+use crate::spec::{ArgSpec, ArgValue, CommandSpec, OptionSpec, SubcommandSpec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Subcommand,
+    Option,
+    Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Candidate {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub kind: Kind,
+}
+
+/// Candidates for the word being typed at the end of `buffer` (empty when the
+/// buffer ends in whitespace). The buffer must start with the spec's command.
+pub fn suggest(spec: &CommandSpec, buffer: &str, remembered: &[FlagValue]) -> Vec<Candidate> {
+    if buffer.contains(['|', ';', '&', '`', '<', '>']) || buffer.contains("$(") {
+        return Vec::new();
+    }
+    let mut tokens: Vec<&str> = buffer.split_whitespace().collect();
+    let partial = if buffer.ends_with(char::is_whitespace) {
+        ""
+    } else {
+        tokens.pop().unwrap_or("")
+    };
+    if tokens.first() != Some(&spec.name.as_str()) {
+        return Vec::new();
+    }
+
+    let mut options: Vec<&OptionSpec> = spec.options.iter().collect();
+    let mut subcommands = &spec.subcommands;
+    let mut level_args: &[ArgSpec] = &spec.args;
+    let mut expecting_subcommand = true;
+    let mut positionals = 0;
+    let mut pending: Option<&OptionSpec> = None;
+    let mut present: Vec<&OptionSpec> = Vec::new();
+    let mut after_double_dash = false;
+
+    for &token in &tokens[1..] {
+        if pending.take().is_some() {
+            continue;
+        }
+        if !after_double_dash && token == "--" {
+            after_double_dash = true;
+            continue;
+        }
+        if !after_double_dash && token.starts_with('-') && token.len() > 1 {
+            let (flag, inline_value) = match token.split_once('=') {
+                Some((flag, _)) if token.starts_with("--") => (flag, true),
+                _ => (token, false),
+            };
+            let opt = options
+                .iter()
+                .find(|o| o.long.as_deref() == Some(flag) || o.short.as_deref() == Some(flag))
+                .copied();
+            present.extend(opt);
+            pending = opt.filter(|o| o.takes_arg && !inline_value);
+            continue;
+        }
+        if expecting_subcommand {
+            if let Some(sub) = find_subcommand(subcommands, token) {
+                options.extend(sub.options.iter());
+                subcommands = &sub.subcommands;
+                level_args = &sub.args;
+                positionals = 0;
+                continue;
+            }
+        }
+        expecting_subcommand = false;
+        positionals += 1;
+    }
+
+    if let Some(opt) = pending {
+        return option_values(&spec.name, opt, partial, remembered);
+    }
+    if !after_double_dash && partial.starts_with('-') {
+        if let Some((name, value)) = partial.split_once('=') {
+            let opt = options.iter().find(|o| o.long.as_deref() == Some(name));
+            return opt.map_or_else(Vec::new, |opt| {
+                option_values(&spec.name, opt, value, remembered)
+                    .into_iter()
+                    .map(|c| Candidate {
+                        text: format!("{name}={}", c.text),
+                        ..c
+                    })
+                    .collect()
+            });
+        }
+        return options
+            .iter()
+            .filter(|o| !excluded(o, &present))
+            .flat_map(|o| [o.long.as_deref(), o.short.as_deref()].map(|flag| (flag, *o)))
+            .filter_map(|(flag, o)| Some((flag?, o)))
+            .filter(|(flag, _)| flag.starts_with(partial))
+            .map(|(flag, o)| Candidate {
+                text: flag.to_string(),
+                description: o.description.clone(),
+                kind: Kind::Option,
+            })
+            .collect();
+    }
+
+    let mut candidates = Vec::new();
+    if expecting_subcommand {
+        candidates.extend(
+            subcommands
+                .iter()
+                .filter(|s| s.name.starts_with(partial))
+                .map(|s| Candidate {
+                    text: s.name.clone(),
+                    description: s.description.clone(),
+                    kind: Kind::Subcommand,
+                }),
+        );
+    }
+    let arg = level_args
+        .get(positionals)
+        .or_else(|| level_args.last().filter(|a| a.variadic));
+    if let Some(arg) = arg {
+        candidates.extend(values(&arg.suggestions, partial));
+    }
+    candidates
+}
+
+/// Whether `opt` is ruled out by the flags already on the line: it is one of
+/// them, or one of them lists it in `exclusive_with`. This matches what the
+/// exported compsys functions let zsh offer.
+fn excluded(opt: &OptionSpec, present: &[&OptionSpec]) -> bool {
+    let names = [opt.long.as_deref(), opt.short.as_deref()];
+    present.iter().any(|p| {
+        std::ptr::eq(*p, opt)
+            || p.exclusive_with
+                .iter()
+                .any(|flag| names.contains(&Some(flag.as_str())))
+    })
+}
+
+fn find_subcommand<'a>(
+    subcommands: &'a [SubcommandSpec],
+    word: &str,
+) -> Option<&'a SubcommandSpec> {
+    subcommands
+        .iter()
+        .find(|s| s.name == word || s.aliases.iter().any(|a| a == word))
+}
+
+/// Values remembered for `opt` (most used first), then its static values.
+fn option_values(
+    command: &str,
+    opt: &OptionSpec,
+    partial: &str,
+    remembered: &[FlagValue],
+) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = flag_key(opt)
+        .map(|flag| values_for(remembered, command, flag))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.value.starts_with(partial))
+        .map(|e| Candidate {
+            text: e.value.clone(),
+            description: Some(match e.count {
+                1 => "used once".to_string(),
+                n => format!("used {n} times"),
+            }),
+            kind: Kind::Value,
+        })
+        .collect();
+    for candidate in values(&opt.arg_suggestions, partial) {
+        if !candidates.iter().any(|c| c.text == candidate.text) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+fn values(values: &[ArgValue], partial: &str) -> Vec<Candidate> {
+    values
+        .iter()
+        .filter(|v| v.value.starts_with(partial))
+        .map(|v| Candidate {
+            text: v.value.clone(),
+            description: v.description.clone(),
+            kind: Kind::Value,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> CommandSpec {
+        toml::from_str(
+            r#"
+            name = "tool"
+            [[options]]
+            long = "--verbose"
+            short = "-v"
+            description = "More output"
+            [[subcommands]]
+            name = "build"
+            aliases = ["b"]
+            description = "Compile"
+            [[subcommands.options]]
+            long = "--profile"
+            takes_arg = true
+            arg_suggestions = [{ value = "dev" }, { value = "release" }]
+            [[subcommands.args]]
+            name = "target"
+            suggestions = [{ value = "all", description = "Everything" }, { value = "lib" }]
+            [[subcommands]]
+            name = "bench"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn texts(candidates: Vec<Candidate>) -> Vec<String> {
+        candidates.into_iter().map(|c| c.text).collect()
+    }
+
+    #[test]
+    fn test_suggest_subcommands_options_and_values() {
+        let spec = spec();
+        assert_eq!(texts(suggest(&spec, "tool b", &[])), vec!["build", "bench"]);
+        assert_eq!(texts(suggest(&spec, "tool bu", &[])), vec!["build"]);
+        assert_eq!(
+            texts(suggest(&spec, "tool -", &[])),
+            vec!["--verbose", "-v"]
+        );
+        assert_eq!(
+            texts(suggest(&spec, "tool b --p", &[])),
+            vec!["--profile"],
+            "aliases select the subcommand"
+        );
+        assert_eq!(
+            texts(suggest(&spec, "tool build --profile ", &[])),
+            vec!["dev", "release"]
+        );
+        assert_eq!(
+            texts(suggest(&spec, "tool build --profile=r", &[])),
+            vec!["--profile=release"]
+        );
+        let targets = suggest(&spec, "tool build -v ", &[]);
+        assert_eq!(targets[0].description.as_deref(), Some("Everything"));
+        assert_eq!(texts(targets), vec!["all", "lib"]);
+        assert!(suggest(&spec, "tool build all ", &[]).is_empty());
+        assert!(suggest(&spec, "other ", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_skips_present_and_exclusive_flags() {
+        let spec: CommandSpec = toml::from_str(
+            r#"
+            name = "tool"
+            [[options]]
+            long = "--json"
+            exclusive_with = ["--yaml"]
+            [[options]]
+            long = "--yaml"
+            [[options]]
+            long = "--out"
+            short = "-o"
+            takes_arg = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            texts(suggest(&spec, "tool --json -", &[])),
+            vec!["--out", "-o"]
+        );
+        assert_eq!(
+            texts(suggest(&spec, "tool -o x --", &[])),
+            vec!["--json", "--yaml"]
+        );
+        assert_eq!(
+            texts(suggest(&spec, "tool --out=x --yaml -", &[])),
+            vec!["--json"]
+        );
+    }
+
+    #[test]
+    fn test_suggest_remembered_flag_values_first() {
+        let spec = spec();
+        let remembered = vec![FlagValue {
+            command: "tool".into(),
+            flag: "--profile".into(),
+            value: "release".into(),
+            count: 4,
+            seen_at: 0,
+        }];
+        let candidates = suggest(&spec, "tool build --profile ", &remembered);
+        assert_eq!(candidates[0].description.as_deref(), Some("used 4 times"));
+        assert_eq!(texts(candidates), vec!["release", "dev"]);
+    }
+}
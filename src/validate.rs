@@ -0,0 +1,266 @@
+//! Spec-aware checks of a typed command line before it runs.
+//!
+//! Specs parsed from help text and completion files are rarely complete, so
+//! an unrecognized word alone is not reported. Flags and subcommands are only
+//! flagged when they are a likely typo of a known one, and missing arguments
+//! only for subcommands that declare required positional arguments (not
+//! variadic or optional ones, such as `docker compose up [service...]`).
+
+use std::fmt;
+
+use crate::spec::{ArgSpec, CommandSpec, OptionSpec, SubcommandSpec};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Warning {
+    UnknownFlag { flag: String, suggestion: String },
+    UnknownSubcommand { word: String, suggestion: String },
+    MissingArg { subcommand: String, arg: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFlag { flag, suggestion } => {
+                write!(f, "unknown flag {flag} (did you mean {suggestion}?)")
+            }
+            Self::UnknownSubcommand { word, suggestion } => {
+                write!(f, "unknown subcommand {word} (did you mean {suggestion}?)")
+            }
+            Self::MissingArg { subcommand, arg } => {
+                write!(f, "{subcommand} is missing <{arg}>")
+            }
+        }
+    }
+}
+
+/// Check a simple command (no pipes or chaining) against its spec.
+pub fn validate(spec: &CommandSpec, command: &str) -> Vec<Warning> {
+    if command.contains(['|', ';', '&', '`', '<', '>']) || command.contains("$(") {
+        return Vec::new();
+    }
+    let mut tokens = command.split_whitespace();
+    if tokens.next() != Some(spec.name.as_str()) {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    let mut options: Vec<&OptionSpec> = spec.options.iter().collect();
+    let mut subcommands = &spec.subcommands;
+    let mut level_args: &[ArgSpec] = &spec.args;
+    let mut leaf: Option<&SubcommandSpec> = None;
+    let mut expecting_subcommand = true;
+    let mut positionals = 0;
+    let mut expect_value = false;
+    let mut after_double_dash = false;
+
+    for token in tokens {
+        if expect_value {
+            expect_value = false;
+            continue;
+        }
+        if !after_double_dash && token == "--" {
+            after_double_dash = true;
+            continue;
+        }
+        if !after_double_dash && token.starts_with("--") {
+            let (name, has_value) = match token.split_once('=') {
+                Some((name, _)) => (name, true),
+                None => (token, false),
+            };
+            match options.iter().find(|o| o.long.as_deref() == Some(name)) {
+                Some(opt) => expect_value = opt.takes_arg && !has_value,
+                None => {
+                    let longs = options.iter().filter_map(|o| o.long.as_deref());
+                    if let Some(suggestion) = closest(name, longs) {
+                        warnings.push(Warning::UnknownFlag {
+                            flag: name.to_string(),
+                            suggestion: suggestion.to_string(),
+                        });
+                    }
+                }
+            }
+            continue;
+        }
+        if !after_double_dash && token.starts_with('-') && token.len() > 1 {
+            // Short flags may be clustered (`-xvf`) or carry a value, so only
+            // an exact match is interpreted.
+            if let Some(opt) = options.iter().find(|o| o.short.as_deref() == Some(token)) {
+                expect_value = opt.takes_arg;
+            }
+            continue;
+        }
+
+        if expecting_subcommand && !subcommands.is_empty() {
+            let found = subcommands
+                .iter()
+                .find(|s| s.name == token || s.aliases.iter().any(|a| a == token));
+            if let Some(sub) = found {
+                options.extend(sub.options.iter());
+                subcommands = &sub.subcommands;
+                level_args = &sub.args;
+                leaf = Some(sub);
+                positionals = 0;
+                continue;
+            }
+            // A level without positional arguments expects a subcommand.
+            if level_args.is_empty() {
+                let names = subcommands.iter().map(|s| s.name.as_str());
+                if let Some(suggestion) = closest(token, names) {
+                    warnings.push(Warning::UnknownSubcommand {
+                        word: token.to_string(),
+                        suggestion: suggestion.to_string(),
+                    });
+                }
+            }
+        }
+        expecting_subcommand = false;
+        positionals += 1;
+    }
+
+    if let Some(sub) = leaf.filter(|sub| sub.subcommands.is_empty()) {
+        let required = sub
+            .args
+            .iter()
+            .skip(positionals)
+            .find(|arg| !arg.variadic && !arg.optional);
+        if let Some(arg) = required {
+            warnings.push(Warning::MissingArg {
+                subcommand: sub.name.clone(),
+                arg: if arg.name.is_empty() {
+                    "arg".to_string()
+                } else {
+                    arg.name.clone()
+                },
+            });
+        }
+    }
+    warnings
+}
+
+/// The candidate closest to `word`, if it is close enough to be a typo.
+fn closest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (word.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance, counting an adjacent transposition as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kubectl_spec() -> CommandSpec {
+        CommandSpec {
+            name: "kubectl".into(),
+            options: vec![OptionSpec {
+                short: Some("-n".into()),
+                long: Some("--namespace".into()),
+                takes_arg: true,
+                ..Default::default()
+            }],
+            subcommands: vec![
+                SubcommandSpec {
+                    name: "logs".into(),
+                    options: vec![OptionSpec {
+                        long: Some("--follow".into()),
+                        ..Default::default()
+                    }],
+                    args: vec![ArgSpec {
+                        name: "pod".into(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                SubcommandSpec {
+                    name: "apply".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_commands_have_no_warnings() {
+        let spec = kubectl_spec();
+        assert!(validate(&spec, "kubectl -n prod logs --follow web-1").is_empty());
+        assert!(validate(&spec, "kubectl --namespace=prod logs web-1").is_empty());
+        // Unknown but not typo-like words are left alone.
+        assert!(validate(&spec, "kubectl apply --server-side").is_empty());
+        assert!(validate(&spec, "kubectl logs web-1 | grep err").is_empty());
+    }
+
+    #[test]
+    fn test_typos_and_missing_args() {
+        let spec = kubectl_spec();
+        assert_eq!(
+            validate(&spec, "kubectl logs --folow web-1"),
+            vec![Warning::UnknownFlag {
+                flag: "--folow".into(),
+                suggestion: "--follow".into(),
+            }]
+        );
+        assert_eq!(
+            validate(&spec, "kubectl lgos web-1"),
+            vec![Warning::UnknownSubcommand {
+                word: "lgos".into(),
+                suggestion: "logs".into(),
+            }]
+        );
+        assert_eq!(
+            validate(&spec, "kubectl -n prod logs")[0].to_string(),
+            "logs is missing <pod>"
+        );
+    }
+
+    #[test]
+    fn test_variadic_and_optional_args_are_not_required() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("package-lock.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("compose.yaml"), "services: {}\n").unwrap();
+        let specs = crate::spec_autogen::generate_specs(dir.path(), &Default::default());
+        let spec = |name: &str| specs.iter().find(|s| s.name == name).unwrap();
+
+        assert!(validate(spec("docker"), "docker compose up -d").is_empty());
+        assert!(validate(spec("docker"), "docker compose logs -f").is_empty());
+        assert!(validate(spec("npm"), "npm run").is_empty());
+
+        let gh = crate::spec_autogen::builtin_spec("gh").unwrap();
+        assert!(validate(&gh, "gh pr view").is_empty());
+        assert_eq!(
+            validate(&gh, "gh pr checkout")[0].to_string(),
+            "checkout is missing <pr>"
+        );
+        let aws = crate::spec_autogen::builtin_spec("aws").unwrap();
+        assert!(validate(&aws, "aws s3 ls").is_empty());
+    }
+}
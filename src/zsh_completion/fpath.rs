@@ -1,5 +1,5 @@
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Literal directories to search for zsh completion files.
 const FPATH_DIRS: &[&str] = &[
@@ -34,6 +34,27 @@ fn fallback_fpath_dirs() -> Vec<PathBuf> {
     dirs
 }
 
+/// Path of the first `_<command>` completion file on the fpath.
+pub(super) fn find_completion_file(command: &str) -> Option<PathBuf> {
+    let file_name = format!("_{command}");
+    resolve_fpath_dirs()
+        .into_iter()
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// Whether some fpath directory other than `exclude` has a `_<command>` file.
+pub(super) fn has_completion_outside(command: &str, exclude: &Path) -> bool {
+    let exclude = exclude
+        .canonicalize()
+        .unwrap_or_else(|_| exclude.to_path_buf());
+    let file_name = format!("_{command}");
+    resolve_fpath_dirs().into_iter().any(|dir| {
+        let dir = dir.canonicalize().unwrap_or(dir);
+        dir != exclude && dir.join(&file_name).is_file()
+    })
+}
+
 pub(super) fn scan_available_commands() -> HashSet<String> {
     let mut commands = HashSet::new();
 
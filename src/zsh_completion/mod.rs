@@ -1,6 +1,7 @@
 //! Parse zsh completion files (`_arguments` specs) into `CommandSpec`.
 
 use std::collections::HashSet;
+use std::path::Path;
 
 use crate::spec::CommandSpec;
 
@@ -12,6 +13,25 @@ pub fn scan_available_commands() -> HashSet<String> {
     fpath::scan_available_commands()
 }
 
+/// Whether a completion function for `command` exists on the fpath outside
+/// `dir`, i.e. one synapse did not write.
+pub fn has_completion_outside(command: &str, dir: &Path) -> bool {
+    fpath::has_completion_outside(command, dir)
+}
+
+/// Parse a compsys completion file into a spec. Returns `None` when the file
+/// yields no options or subcommands (e.g. dispatcher-only functions).
+pub fn parse_completion_file(command: &str, path: &Path) -> Option<CommandSpec> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let spec = parser::parse_zsh_completion(command, &content);
+    (!spec.options.is_empty() || !spec.subcommands.is_empty()).then_some(spec)
+}
+
+/// Parse the system (fpath) completion file for `command`, if there is one.
+pub fn load_system_spec(command: &str) -> Option<CommandSpec> {
+    parse_completion_file(command, &fpath::find_completion_file(command)?)
+}
+
 pub async fn try_completion_generator(
     command: &str,
     timeout: std::time::Duration,
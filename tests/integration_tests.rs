@@ -168,6 +168,21 @@ fn test_translate_query_too_short() {
     );
 }
 
+#[test]
+fn test_translate_execute_reports_plain_error() {
+    // Outside the plugin, failures are ordinary errors rather than TSV
+    let output = cargo_bin_cmd!("synapse")
+        .args(["translate", "hi", "--execute"])
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .expect("Failed to run synapse translate");
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("too short"), "got: {stderr}");
+}
+
 #[test]
 fn test_add_blocked_command() {
     // Dangerous commands should be blocked by the discovery blocklist
@@ -182,6 +197,106 @@ fn test_add_blocked_command() {
     );
 }
 
+#[cfg(unix)]
+#[test]
+fn test_add_subcommand_path_saves_bundle() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = tempfile::tempdir().unwrap();
+    let bin = home.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let tool = bin.join("synapse-fake-cloud");
+    std::fs::write(
+        &tool,
+        "#!/bin/sh\n\
+         [ \"$1 $2\" = \"storage --help\" ] || exit 1\n\
+         printf 'Usage: synapse-fake-cloud storage\\n\\nOptions:\\n  --bucket <name>  Bucket to use\\n  --recursive      Include subdirectories\\n'\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["add", "synapse-fake-cloud", "storage"])
+        .env("HOME", home.path())
+        .env("PATH", path)
+        .output()
+        .expect("Failed to run synapse add");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "got: {stdout}");
+    assert!(
+        stdout.contains("Discovered synapse-fake-cloud storage: 2 options, 0 subcommands"),
+        "got: {stdout}"
+    );
+    assert!(
+        stdout.contains("Options: --bucket, --recursive"),
+        "got: {stdout}"
+    );
+    let bundle = home.path().join(".synapse/specs/synapse-fake-cloud.toml");
+    let bundle = std::fs::read_to_string(bundle).unwrap();
+    assert!(bundle.contains("name = \"storage\""), "got: {bundle}");
+    let completion = home.path().join(".synapse/completions/_synapse-fake-cloud");
+    assert!(std::fs::read_to_string(completion)
+        .unwrap()
+        .contains("--bucket"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_add_from_path_reports_each_command() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = tempfile::tempdir().unwrap();
+    let bin = home.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let scripts = [
+        (
+            "synapse-fake-build",
+            "[ \"$1\" = --help ] || exit 1\nprintf 'Options:\\n  --release  Optimize\\n'\n",
+        ),
+        ("synapse-fake-silent", "exit 1\n"),
+    ];
+    for (name, body) in scripts {
+        let path = bin.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{body}")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let output = cargo_bin_cmd!("synapse")
+        .args([
+            "add",
+            "--from-path",
+            "synapse-fake-*",
+            "synapse-no-such-tool",
+        ])
+        .env("HOME", home.path())
+        .env("PATH", path)
+        .output()
+        .expect("Failed to run synapse add --from-path");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "got: {stdout}");
+    assert!(stdout.contains("Added 1 completions"), "got: {stdout}");
+    assert!(
+        stdout.contains("via --help (1): synapse-fake-build"),
+        "got: {stdout}"
+    );
+    assert!(
+        stdout.contains("no parseable --help output (1): synapse-fake-silent"),
+        "got: {stdout}"
+    );
+    assert!(
+        stdout.contains("not found on PATH (1): synapse-no-such-tool"),
+        "got: {stdout}"
+    );
+    assert!(home
+        .path()
+        .join(".synapse/completions/_synapse-fake-build")
+        .exists());
+}
+
 #[test]
 fn test_scan_with_makefile() {
     let dir = tempfile::tempdir().unwrap();
@@ -218,6 +333,202 @@ fn test_scan_with_makefile() {
     );
 }
 
+#[test]
+fn test_local_scripts_lists_project_executables() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("scripts")).unwrap();
+    for (path, mode) in [("scripts/deploy.sh", 0o755), ("scripts/notes.txt", 0o644)] {
+        let path = dir.path().join(path);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+    std::fs::write(dir.path().join("cli.js"), "").unwrap();
+    std::fs::write(dir.path().join("package.json"), r#"{"bin": "./cli.js"}"#).unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["local-scripts", "--cwd"])
+        .arg(dir.path())
+        .output()
+        .expect("Failed to run synapse local-scripts");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "./cli.js\n./scripts/deploy.sh\n"
+    );
+}
+
+#[test]
+fn test_scan_defers_terraform_to_installed_completion() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("infra");
+    let output_dir = dir.path().join("completions");
+    let system = dir.path().join("site-functions");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::create_dir_all(&system).unwrap();
+    std::fs::write(project.join("main.tf"), "terraform {}\n").unwrap();
+
+    let scan = |fpath: &std::path::Path| {
+        cargo_bin_cmd!("synapse")
+            .args(["scan", "--output-dir", output_dir.to_str().unwrap()])
+            .current_dir(&project)
+            .env("HOME", dir.path())
+            .env("FPATH", fpath)
+            .output()
+            .expect("Failed to run synapse scan")
+    };
+
+    assert!(scan(&system).status.success());
+    assert!(output_dir.join("_terraform").exists());
+
+    std::fs::write(system.join("_terraform"), "#compdef terraform\n").unwrap();
+    assert!(scan(&system).status.success());
+    assert!(
+        !output_dir.join("_terraform").exists(),
+        "the installed _terraform should not be shadowed"
+    );
+}
+
+#[test]
+fn test_scan_watch_regenerates_on_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("completions");
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("synapse"))
+        .args([
+            "scan",
+            "--watch",
+            "--no-gap-check",
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+        ])
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to run synapse scan --watch");
+
+    let completion = output_dir.join("_make");
+    let wait_for = |exists: bool| {
+        (0..100).any(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            completion.exists() == exists
+        })
+    };
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    std::fs::write(dir.path().join("Makefile"), "build:\n\techo build\n").unwrap();
+    let created = wait_for(true);
+    std::fs::remove_file(dir.path().join("Makefile")).unwrap();
+    let removed = wait_for(false);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(created, "_make was not generated after adding a Makefile");
+    assert!(removed, "_make was not removed after deleting the Makefile");
+}
+
+#[test]
+fn test_make_targets_with_descriptions() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("completions");
+
+    std::fs::write(
+        dir.path().join("Makefile"),
+        ".PHONY: build test\n## Compile the project\nbuild:\n\techo build\ntest: ## Run tests\n\techo test\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args([
+            "scan",
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+            "--force",
+        ])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to run synapse scan");
+
+    assert!(output.status.success());
+    let content = std::fs::read_to_string(output_dir.join("_make")).unwrap();
+    assert!(
+        content.contains("synapse make-targets") && !content.contains("Compile"),
+        "Expected a generator rather than baked-in targets, got: {content}"
+    );
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["make-targets", "--cwd", dir.path().to_str().unwrap()])
+        .output()
+        .expect("Failed to run synapse make-targets");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "build\tCompile the project\ntest\tRun tests\n"
+    );
+}
+
+#[test]
+fn test_package_scripts_with_commands() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"build": "tsc -p .", "deploy": "npm run build && firebase deploy"}}"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["scan", "--force", "--output-dir"])
+        .arg(dir.path().join("completions"))
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to run synapse scan");
+    assert!(output.status.success());
+    let content = std::fs::read_to_string(dir.path().join("completions/_npm")).unwrap();
+    assert!(
+        content.contains("synapse package-scripts") && !content.contains("firebase"),
+        "Expected a generator rather than baked-in scripts, got: {content}"
+    );
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["package-scripts", "--cwd"])
+        .arg(dir.path())
+        .output()
+        .expect("Failed to run synapse package-scripts");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "build\ttsc -p .\ndeploy\tnpm run build && firebase deploy\n"
+    );
+}
+
+#[test]
+fn test_scan_outside_project_keeps_previous_completions() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("project");
+    let elsewhere = dir.path().join("elsewhere");
+    let output_dir = dir.path().join("completions");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::create_dir_all(&elsewhere).unwrap();
+    std::fs::write(project.join("Makefile"), "build:\n\techo build\n").unwrap();
+
+    let scan = |cwd: &std::path::Path| {
+        cargo_bin_cmd!("synapse")
+            .args(["scan", "--output-dir", output_dir.to_str().unwrap()])
+            .current_dir(cwd)
+            .env("HOME", dir.path())
+            .output()
+            .expect("Failed to run synapse scan")
+    };
+
+    assert!(scan(&project).status.success());
+    assert!(output_dir.join("_make").exists());
+    assert!(scan(&elsewhere).status.success());
+    assert!(
+        output_dir.join("_make").exists(),
+        "leaving the project should not drop its completions"
+    );
+}
+
 #[test]
 fn test_scan_empty_dir() {
     let dir = tempfile::tempdir().unwrap();
@@ -258,6 +569,136 @@ fn test_init_output_includes_fpath_unconditionally() {
     );
 }
 
+#[test]
+fn test_invalid_confirm_risk_keeps_confirmation_on() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join("synapse");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[security]\nconfirm_risk = \"hgih\"\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .env("XDG_CONFIG_HOME", dir.path())
+        .output()
+        .expect("Failed to run synapse init");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("typeset -gi _SYNAPSE_CONFIRM_RISK=3"),
+        "Expected fallback to high, got: {stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unknown security.confirm_risk \"hgih\""));
+}
+
+/// Source the plugin in a plain zsh with `zle` stubbed out and run `script`
+/// against its widget functions. Returns None when zsh is not installed.
+fn run_plugin_script(script: &str) -> Option<String> {
+    let home = tempfile::tempdir().unwrap();
+    let plugin = concat!(env!("CARGO_MANIFEST_DIR"), "/plugin/synapse.zsh");
+    let output = match std::process::Command::new("zsh")
+        .args([
+            "-f",
+            "-c",
+            &format!("source {plugin:?}\nzle() {{ : }}\n{script}"),
+        ])
+        .env("HOME", home.path())
+        .env_remove("SYNAPSE_BIN")
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => panic!("failed to run zsh: {e}"),
+    };
+    assert!(
+        output.status.success(),
+        "plugin script failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[test]
+fn test_plugin_new_plan_resets_held_step() {
+    // A risky step held by an earlier plan must not let the next plan's
+    // risky first step skip the confirm gate.
+    let Some(stdout) = run_plugin_script(
+        r#"
+        _SYNAPSE_CONFIRM_RISK=3
+        BUFFER='? clean up'
+        _synapse_start_plan $'plan\t1\trm -rf build\thigh\tdeletes files'
+        BUFFER='? publish'
+        _synapse_start_plan $'plan\t1\tgit push --force\thigh\trewrites history'
+        print -r -- "held=$_SYNAPSE_PLAN_HELD buffer=[$BUFFER]"
+        "#,
+    ) else {
+        return;
+    };
+    assert_eq!(stdout, "held=1 buffer=[]\n");
+}
+
+#[test]
+fn test_plugin_enter_resumes_held_plan_step() {
+    // Holding a step clears the `? query`, so Enter inserts the step instead
+    // of translating the query again.
+    let Some(stdout) = run_plugin_script(
+        r#"
+        _SYNAPSE_CONFIRM_RISK=3
+        BUFFER='? clean up'
+        _synapse_start_plan $'plan\t1\trm -rf build\thigh\tdeletes files'
+        print -r -- "held buffer=[$BUFFER]"
+        _synapse_accept_line
+        print -r -- "after enter buffer=[$BUFFER] held=$_SYNAPSE_PLAN_HELD"
+        "#,
+    ) else {
+        return;
+    };
+    assert_eq!(
+        stdout,
+        "held buffer=[]\nafter enter buffer=[rm -rf build] held=0\n"
+    );
+}
+
+#[test]
+fn test_plugin_enter_expands_snippets_without_running() {
+    let Some(stdout) = run_plugin_script(
+        r#"
+        zle() { [[ "$1" == .accept-line ]] && print -r -- "ran [$BUFFER]" }
+        _SYNAPSE_SNIPPETS=(gst 'git status')
+        BUFFER=gst; CURSOR=3
+        _synapse_accept_line
+        print -r -- "buffer=[$BUFFER]"
+        _synapse_accept_line
+        "#,
+    ) else {
+        return;
+    };
+    assert_eq!(stdout, "buffer=[git status]\nran [git status]\n");
+}
+
+#[test]
+fn test_plugin_team_triggers_never_shadow_commands() {
+    let Some(stdout) = run_plugin_script(
+        r#"
+        alias ll='ls -l'
+        _SYNAPSE_SNIPPETS=(ll 'rm -rf ~' cd 'rm -rf ~' kgp 'kubectl get pods')
+        _SYNAPSE_TEAM_TRIGGERS=(ll cd kgp)
+        for BUFFER in ll cd kgp; do
+            CURSOR=${#BUFFER}
+            _synapse_expand_snippet
+            print -r -- "$? [$BUFFER]"
+        done
+        "#,
+    ) else {
+        return;
+    };
+    assert_eq!(stdout, "1 [ll]\n1 [cd]\n0 [kubectl get pods]\n");
+}
+
 #[test]
 fn test_translate_passes_recent_commands_and_env_hints() {
     // Verify the CLI accepts --recent-command and --env-hint flags without error.
@@ -291,3 +732,311 @@ fn test_translate_passes_recent_commands_and_env_hints() {
         "translate with flags should not crash"
     );
 }
+
+#[test]
+fn test_pins_go_through_policy_and_risk() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join("config").join("synapse");
+    let project = dir.path().join("project");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[general]\nlanguage = \"en\"\n\n[[security.policy]]\naction = \"deny\"\nmatch = \"regex\"\npattern = 'curl .*\\|\\s*(ba)?sh'\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.join(".synapse.toml"),
+        "pins = [\"curl -fsSL https://example.com/i.sh | sh\", \"rm -rf build\"]\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["pin", "list", "--tsv", "--cwd"])
+        .arg(&project)
+        .env("HOME", dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join("config"))
+        .output()
+        .expect("Failed to run synapse pin list");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "list\t1\trm -rf build\tpin\tdeletes files recursively; not recoverable\tcommand\thigh\n"
+    );
+    let audit = std::fs::read_to_string(dir.path().join(".synapse/policy-audit.log")).unwrap();
+    assert!(audit.contains("i.sh | sh"), "{audit}");
+}
+
+#[test]
+fn test_policy_test_reports_deciding_rule() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join("synapse");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[security]\ncommand_blocklist = [\"curl -u\"]\n\n[[security.policy]]\naction = \"allow\"\npattern = \"curl -u ci\"\n",
+    )
+    .unwrap();
+
+    let run = |command: &str| {
+        let output = cargo_bin_cmd!("synapse")
+            .args(["policy", "test", command, "--cwd", "/tmp"])
+            .env("XDG_CONFIG_HOME", dir.path())
+            .output()
+            .expect("Failed to run synapse policy test");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let denied = run("curl -u admin:pw http://x");
+    assert!(
+        denied.starts_with("deny\tcommand_blocklist"),
+        "Expected blocklist deny, got: {denied}"
+    );
+    let allowed = run("curl -u ci http://x");
+    assert!(
+        allowed.starts_with("allow\tpolicy[0]"),
+        "Expected policy allow, got: {allowed}"
+    );
+}
+
+#[test]
+fn test_translate_offline_rejects_remote_llm() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join("synapse");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[llm]\nbase_url = \"https://api.example.com\"\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["translate", "list all files", "--cwd", "/tmp"])
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("SYNAPSE_OFFLINE", "1")
+        .output()
+        .expect("Failed to run synapse translate");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("error\toffline"),
+        "Expected offline error TSV, got: {stdout}"
+    );
+}
+
+#[test]
+fn test_completions_sync_removes_uninstalled_commands() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("completions");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let header = |source: &str| {
+        format!("#compdef x\n# Auto-generated by synapse -- do not edit manually\n# Source: {source}\n# Export version: 2\n")
+    };
+    std::fs::write(
+        output_dir.join("_synapse-no-such-tool"),
+        header("discovered (parsed from --help)"),
+    )
+    .unwrap();
+    std::fs::write(output_dir.join("_make"), header("project-auto")).unwrap();
+    std::fs::write(output_dir.join("_handwritten"), "#compdef handwritten\n").unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args([
+            "completions",
+            "sync",
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+        ])
+        .env("HOME", dir.path())
+        .output()
+        .expect("Failed to run synapse completions sync");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("_synapse-no-such-tool"),
+        "Expected removal report, got: {stdout}"
+    );
+    assert!(!output_dir.join("_synapse-no-such-tool").exists());
+    assert!(output_dir.join("_make").exists());
+    assert!(output_dir.join("_handwritten").exists());
+}
+
+#[test]
+fn test_background_completions_sync_never_removes() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("completions");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    // A tool only on PATH inside a project (direnv, venv, shims).
+    std::fs::write(
+        output_dir.join("_synapse-venv-tool"),
+        "#compdef x\n# Auto-generated by synapse -- do not edit manually\n# Source: discovered (parsed from --help)\n# Export version: 2\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args([
+            "completions",
+            "sync",
+            "--background",
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+        ])
+        .env("HOME", dir.path())
+        .output()
+        .expect("Failed to run synapse completions sync");
+
+    assert!(output.status.success());
+    assert!(output_dir.join("_synapse-venv-tool").exists());
+    assert!(dir.path().join(".synapse/last-completions-sync").exists());
+}
+
+#[test]
+fn test_status_json() {
+    let home = tempfile::tempdir().unwrap();
+    let completions = home.path().join(".synapse").join("completions");
+    std::fs::create_dir_all(&completions).unwrap();
+    std::fs::write(
+        completions.join("_deployctl"),
+        "#compdef deployctl\n# Auto-generated by synapse -- do not edit manually\n# Source: discovered (parsed from --help)\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["status", "--json"])
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join(".config"))
+        .env("SYNAPSE_OFFLINE", "1")
+        .output()
+        .expect("Failed to run synapse status");
+    assert!(output.status.success());
+
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(status["config_found"], false);
+    assert_eq!(status["completions"]["discovered"], 1);
+    assert!(status["cache_bytes"]["completions"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn test_suggest_json_from_project_spec() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::write(project.path().join("compose.yml"), "services: {}\n").unwrap();
+
+    let output = cargo_bin_cmd!("synapse")
+        .args(["suggest", "docker compose u", "--json", "--cwd"])
+        .arg(project.path())
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join(".config"))
+        .output()
+        .expect("Failed to run synapse suggest");
+    assert!(output.status.success());
+
+    let candidates: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        candidates,
+        serde_json::json!([{ "text": "up", "description": "Start services", "kind": "subcommand" }])
+    );
+}
+
+#[test]
+fn test_run_generator_tsv_format() {
+    let output = cargo_bin_cmd!("synapse")
+        .args([
+            "run-generator",
+            "printf 'main\\tFix login\\nbuild:prod\\n'",
+            "--cwd",
+            "/tmp",
+            "--format",
+            "tsv",
+        ])
+        .output()
+        .expect("Failed to run synapse run-generator");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "main:Fix login\nbuild\\:prod\n");
+}
+
+#[test]
+fn test_spec_import_rejects_plain_http() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = cargo_bin_cmd!("synapse")
+        .args(["spec", "import", "http://specs.example.com/deployctl.toml"])
+        .env("HOME", dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join("config"))
+        .output()
+        .expect("Failed to run synapse spec import");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("must use https"), "got: {stderr}");
+    assert!(!dir.path().join(".synapse").join("specs").exists());
+}
+
+#[test]
+fn test_spec_sync_requires_pinned_git_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("specs");
+    std::fs::create_dir_all(&repo).unwrap();
+    let bundle = |name: &str| {
+        format!(
+            "[bundle]\nformat_version = 1\nsynapse_version = \"0.0.0\"\nexported_at = 0\ngenerators = []\n\n[spec]\nname = \"{name}\"\n\n[[spec.subcommands]]\nname = \"rollout\"\n"
+        )
+    };
+    std::fs::write(repo.join("deployctl.toml"), bundle("deployctl")).unwrap();
+    std::fs::write(repo.join("handwritten.toml"), bundle("handwritten")).unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&["init", "--quiet", "--initial-branch=main"]);
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "specs"]);
+    let commit = git(&["rev-parse", "HEAD"]);
+
+    let output_dir = dir.path().join("completions");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    std::fs::write(output_dir.join("_handwritten"), "#compdef handwritten\n").unwrap();
+    let config_dir = dir.path().join("config").join("synapse");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let sync = |rev: &str| {
+        std::fs::write(
+            config_dir.join("config.toml"),
+            format!(
+                "[[spec.remote_sources]]\ngit = \"{}\"\nrev = \"{rev}\"\n",
+                repo.display()
+            ),
+        )
+        .unwrap();
+        cargo_bin_cmd!("synapse")
+            .args(["spec", "sync", "--output-dir", output_dir.to_str().unwrap()])
+            .env("HOME", dir.path())
+            .env("XDG_CONFIG_HOME", dir.path().join("config"))
+            .output()
+            .expect("Failed to run synapse spec sync")
+    };
+
+    let output = sync("main");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("full commit hash"), "got: {stderr}");
+    assert!(!output_dir.join("_deployctl").exists());
+
+    let output = sync(&commit);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output_dir.join("_deployctl").exists(), "got: {stderr}");
+    assert!(stderr.contains("'handwritten' already has a completion"));
+    assert_eq!(
+        std::fs::read_to_string(output_dir.join("_handwritten")).unwrap(),
+        "#compdef handwritten\n"
+    );
+}